@@ -65,3 +65,432 @@ func CreateZeroSignature() *[96]byte {
 	defer resp.destroy()
 	return resp.copyAsArray()
 }
+
+func BatchVerify(flattenedSignatures SliceRefUint8, flattenedMessages SliceRefUint8, messageSizes SliceRefUint, flattenedPublicKeys SliceRefUint8) (bool, uint64) {
+	resp := (*BLSBatchVerify)(C.batch_verify((C.slice_ref_uint8_t)(flattenedSignatures),
+		(C.slice_ref_uint8_t)(flattenedMessages),
+		(C.slice_ref_size_t)(messageSizes),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	return bool(resp.valid), uint64(resp.first_invalid_index)
+}
+
+func PrivateKeyProvePossession(rawPrivateKey SliceRefUint8) *[96]byte {
+	resp := (*ByteArray96)(C.private_key_prove_possession((C.slice_ref_uint8_t)(rawPrivateKey)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func PublicKeyVerifyPossession(rawPublicKey SliceRefUint8, rawProof SliceRefUint8) bool {
+	resp := C.public_key_verify_possession((C.slice_ref_uint8_t)(rawPublicKey),
+		(C.slice_ref_uint8_t)(rawProof))
+	return bool(resp)
+}
+
+func AggregatePublicKeys(flattenedPublicKeys SliceRefUint8) *[48]byte {
+	resp := (*ByteArray48)(C.aggregate_public_keys((C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func FastAggregateVerify(signature SliceRefUint8, message SliceRefUint8, flattenedPublicKeys SliceRefUint8) bool {
+	resp := C.fast_aggregate_verify((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys))
+	return bool(resp)
+}
+
+func AggregateResult(flattenedSignatures SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.aggregate_result((C.slice_ref_uint8_t)(flattenedSignatures)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func AggregatePublicKeysResult(flattenedPublicKeys SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.aggregate_public_keys_result((C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func VerifyResult(signature SliceRefUint8, flattenedDigests SliceRefUint8, flattenedPublicKeys SliceRefUint8) (bool, error) {
+	resp := (*resultBLSBool)(C.verify_result((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(flattenedDigests),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return false, err
+	}
+	return bool(resp.value), nil
+}
+
+func HashVerifyResult(signature SliceRefUint8, flattenedMessages SliceRefUint8, messageSizes SliceRefUint, flattenedPublicKeys SliceRefUint8) (bool, error) {
+	resp := (*resultBLSBool)(C.hash_verify_result((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(flattenedMessages),
+		(C.slice_ref_size_t)(messageSizes),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return false, err
+	}
+	return bool(resp.value), nil
+}
+
+func FastAggregateVerifyResult(signature SliceRefUint8, message SliceRefUint8, flattenedPublicKeys SliceRefUint8) (bool, error) {
+	resp := (*resultBLSBool)(C.fast_aggregate_verify_result((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return false, err
+	}
+	return bool(resp.value), nil
+}
+
+func PrivateKeySignResult(rawPrivateKey SliceRefUint8, message SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.private_key_sign_result((C.slice_ref_uint8_t)(rawPrivateKey),
+		(C.slice_ref_uint8_t)(message)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func PrivateKeyPublicKeyResult(rawPrivateKey SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.private_key_public_key_result((C.slice_ref_uint8_t)(rawPrivateKey)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func PrivateKeyProvePossessionResult(rawPrivateKey SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.private_key_prove_possession_result((C.slice_ref_uint8_t)(rawPrivateKey)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func PublicKeyVerifyPossessionResult(rawPublicKey SliceRefUint8, rawProof SliceRefUint8) (bool, error) {
+	resp := (*resultBLSBool)(C.public_key_verify_possession_result((C.slice_ref_uint8_t)(rawPublicKey),
+		(C.slice_ref_uint8_t)(rawProof)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return false, err
+	}
+	return bool(resp.value), nil
+}
+
+func DrandVerifyBeacon(scheme DrandScheme, publicKey SliceRefUint8, round uint64, previousSignature SliceRefUint8, signature SliceRefUint8) bool {
+	resp := C.drand_verify_beacon((C.DrandScheme_t)(scheme),
+		(C.slice_ref_uint8_t)(publicKey),
+		C.uint64_t(round),
+		(C.slice_ref_uint8_t)(previousSignature),
+		(C.slice_ref_uint8_t)(signature))
+	return bool(resp)
+}
+
+func DrandVerifyBeacons(scheme DrandScheme, publicKey SliceRefUint8, firstRound uint64, previousSignature SliceRefUint8, flattenedSignatures SliceRefUint8) (bool, uint64) {
+	resp := (*BLSBatchVerify)(C.drand_verify_beacons((C.DrandScheme_t)(scheme),
+		(C.slice_ref_uint8_t)(publicKey),
+		C.uint64_t(firstRound),
+		(C.slice_ref_uint8_t)(previousSignature),
+		(C.slice_ref_uint8_t)(flattenedSignatures)))
+	defer resp.destroy()
+	return bool(resp.valid), uint64(resp.first_invalid_index)
+}
+
+func ThresholdSplitPrivateKey(rawPrivateKey SliceRefUint8, threshold uint32, shares uint32) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.threshold_split_private_key((C.slice_ref_uint8_t)(rawPrivateKey),
+		C.uint32_t(threshold),
+		C.uint32_t(shares)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func ThresholdPartialSign(rawShare SliceRefUint8, shareIndex uint32, message SliceRefUint8) *[100]byte {
+	resp := (*ByteArray100)(C.threshold_partial_sign((C.slice_ref_uint8_t)(rawShare),
+		C.uint32_t(shareIndex),
+		(C.slice_ref_uint8_t)(message)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func ThresholdPartialVerify(partialSignature SliceRefUint8, message SliceRefUint8, rawSharePublicKey SliceRefUint8) bool {
+	resp := C.threshold_partial_verify((C.slice_ref_uint8_t)(partialSignature),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(rawSharePublicKey))
+	return bool(resp)
+}
+
+func ThresholdCombine(flattenedPartialSignatures SliceRefUint8, threshold uint32) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.threshold_combine((C.slice_ref_uint8_t)(flattenedPartialSignatures),
+		C.uint32_t(threshold)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSKeyHandleGenerate() *BLSKeyHandle {
+	return (*BLSKeyHandle)(C.bls_key_handle_generate())
+}
+
+func BLSKeyHandleFromSeed(rawSeed *ByteArray32) *BLSKeyHandle {
+	return (*BLSKeyHandle)(C.bls_key_handle_from_seed((*C.uint8_32_array_t)(rawSeed)))
+}
+
+func BLSKeyHandleFromBytes(rawPrivateKey SliceRefUint8) (*BLSKeyHandle, error) {
+	resp := (*resultBLSKeyHandle)(C.bls_key_handle_from_bytes((C.slice_ref_uint8_t)(rawPrivateKey)))
+	return takeBLSKeyHandle(resp)
+}
+
+func BLSKeyHandleSign(handle *BLSKeyHandle, message SliceRefUint8) *[96]byte {
+	resp := (*ByteArray96)(C.bls_key_handle_sign((*C.InnerBLSKeyHandle_t)(handle),
+		(C.slice_ref_uint8_t)(message)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func BLSKeyHandlePublicKey(handle *BLSKeyHandle) *[48]byte {
+	resp := (*ByteArray48)(C.bls_key_handle_public_key((*C.InnerBLSKeyHandle_t)(handle)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func BLSKeyHandleExport(handle *BLSKeyHandle) *[32]byte {
+	resp := (*ByteArray32)(C.bls_key_handle_export((*C.InnerBLSKeyHandle_t)(handle)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func takeBLSKeyHandle(resp *resultBLSKeyHandle) (*BLSKeyHandle, error) {
+	// take out the pointer from the result to ensure it doesn't get freed
+	handle := (*BLSKeyHandle)(resp.value)
+	resp.value = nil
+	defer resp.destroy()
+
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+
+	return handle, nil
+}
+
+func BLSDeriveMasterKey(seed SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_derive_master_key((C.slice_ref_uint8_t)(seed)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSDeriveChildKey(rawParentKey SliceRefUint8, index uint32) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_derive_child_key((C.slice_ref_uint8_t)(rawParentKey),
+		C.uint32_t(index)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSDerivePathKey(seed SliceRefUint8, path SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_derive_path_key((C.slice_ref_uint8_t)(seed),
+		(C.slice_ref_uint8_t)(path)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSKeyHandleFromPath(seed SliceRefUint8, path SliceRefUint8) (*BLSKeyHandle, error) {
+	resp := (*resultBLSKeyHandle)(C.bls_key_handle_from_path((C.slice_ref_uint8_t)(seed),
+		(C.slice_ref_uint8_t)(path)))
+	return takeBLSKeyHandle(resp)
+}
+
+func HashDst(message SliceRefUint8, dst SliceRefUint8) *[96]byte {
+	resp := (*ByteArray96)(C.hash_dst((C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(dst)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func PrivateKeySignDst(rawPrivateKey SliceRefUint8, message SliceRefUint8, dst SliceRefUint8) *[96]byte {
+	resp := (*ByteArray96)(C.private_key_sign_dst((C.slice_ref_uint8_t)(rawPrivateKey),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(dst)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func VerifyDst(signature SliceRefUint8, message SliceRefUint8, publicKey SliceRefUint8, dst SliceRefUint8) bool {
+	resp := C.verify_dst((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(publicKey),
+		(C.slice_ref_uint8_t)(dst))
+	return bool(resp)
+}
+
+func HashVerifyDst(signature SliceRefUint8, flattenedMessages SliceRefUint8, messageSizes SliceRefUint, flattenedPublicKeys SliceRefUint8, dst SliceRefUint8) bool {
+	resp := C.hash_verify_dst((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(flattenedMessages),
+		(C.slice_ref_size_t)(messageSizes),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys),
+		(C.slice_ref_uint8_t)(dst))
+	return bool(resp)
+}
+
+func PublicKeyValidate(rawPublicKey SliceRefUint8) error {
+	resp := (*resultBLSVoid)(C.public_key_validate((C.slice_ref_uint8_t)(rawPublicKey)))
+	defer resp.destroy()
+	return CheckErr(resp)
+}
+
+func SignatureValidate(rawSignature SliceRefUint8) error {
+	resp := (*resultBLSVoid)(C.signature_validate((C.slice_ref_uint8_t)(rawSignature)))
+	defer resp.destroy()
+	return CheckErr(resp)
+}
+
+func G1Compress(rawPoint SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.g1_compress((C.slice_ref_uint8_t)(rawPoint)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func G1Decompress(rawPoint SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.g1_decompress((C.slice_ref_uint8_t)(rawPoint)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func G2Compress(rawPoint SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.g2_compress((C.slice_ref_uint8_t)(rawPoint)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func G2Decompress(rawPoint SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.g2_decompress((C.slice_ref_uint8_t)(rawPoint)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSPublicKeyCacheEnable(capacity uint64) {
+	C.bls_public_key_cache_enable(C.uint64_t(capacity))
+}
+
+func BLSPublicKeyCacheClear() {
+	C.bls_public_key_cache_clear()
+}
+
+func BLSPublicKeyCacheStatsGet() BLSPublicKeyCacheStatsGo {
+	resp := (*BLSPublicKeyCacheStats)(C.bls_public_key_cache_stats())
+	defer resp.destroy()
+	return resp.copy()
+}
+
+func BLSAggregatorNew() *BLSAggregator {
+	return (*BLSAggregator)(C.bls_aggregator_new())
+}
+
+func BLSAggregatorAdd(aggregator *BLSAggregator, signature SliceRefUint8) bool {
+	return bool(C.bls_aggregator_add((*C.InnerBLSAggregator_t)(aggregator), (C.slice_ref_uint8_t)(signature)))
+}
+
+func BLSAggregatorSubtract(aggregator *BLSAggregator, signature SliceRefUint8) bool {
+	return bool(C.bls_aggregator_subtract((*C.InnerBLSAggregator_t)(aggregator), (C.slice_ref_uint8_t)(signature)))
+}
+
+func BLSAggregatorCount(aggregator *BLSAggregator) uint64 {
+	return uint64(C.bls_aggregator_count((*C.InnerBLSAggregator_t)(aggregator)))
+}
+
+func BLSAggregatorReset(aggregator *BLSAggregator) {
+	C.bls_aggregator_reset((*C.InnerBLSAggregator_t)(aggregator))
+}
+
+func BLSAggregatorFinalize(aggregator *BLSAggregator) *[96]byte {
+	resp := (*ByteArray96)(C.bls_aggregator_finalize((*C.InnerBLSAggregator_t)(aggregator)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func BLSKeystoreEncrypt(rawPrivateKey SliceRefUint8, password SliceRefUint8, kdf BLSKeystoreKdf) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_keystore_encrypt((C.slice_ref_uint8_t)(rawPrivateKey),
+		(C.slice_ref_uint8_t)(password),
+		(C.BLSKeystoreKdf_t)(kdf)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSKeystoreDecrypt(keystore SliceRefUint8, password SliceRefUint8) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_keystore_decrypt((C.slice_ref_uint8_t)(keystore),
+		(C.slice_ref_uint8_t)(password)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSKeyHandleToKeystore(handle *BLSKeyHandle, password SliceRefUint8, kdf BLSKeystoreKdf) ([]byte, error) {
+	resp := (*resultBLSBytes)(C.bls_key_handle_to_keystore((*C.InnerBLSKeyHandle_t)(handle),
+		(C.slice_ref_uint8_t)(password),
+		(C.BLSKeystoreKdf_t)(kdf)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func BLSKeyHandleFromKeystore(keystore SliceRefUint8, password SliceRefUint8) (*BLSKeyHandle, error) {
+	resp := (*resultBLSKeyHandle)(C.bls_key_handle_from_keystore((C.slice_ref_uint8_t)(keystore),
+		(C.slice_ref_uint8_t)(password)))
+	return takeBLSKeyHandle(resp)
+}
+
+// BlockVerifyBLSAggregate returns whether the aggregate signature of a block is valid, and if not,
+// the BLSError code of the failure (0 if the signature just doesn't verify) and the index of the
+// offending message.
+func BlockVerifyBLSAggregate(aggregateSignature SliceRefUint8, flattenedMessages SliceRefUint8, messageSizes SliceRefUint, flattenedPublicKeys SliceRefUint8) (bool, BLSError, uint64) {
+	resp := (*BLSBlockVerify)(C.block_verify_bls_aggregate((C.slice_ref_uint8_t)(aggregateSignature),
+		(C.slice_ref_uint8_t)(flattenedMessages),
+		(C.slice_ref_size_t)(messageSizes),
+		(C.slice_ref_uint8_t)(flattenedPublicKeys)))
+	defer resp.destroy()
+	return bool(resp.valid), BLSError(resp.error_code), uint64(resp.invalid_index)
+}
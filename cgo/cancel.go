@@ -0,0 +1,70 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+
+// The cancellable operations return an error with the FCPResponseStatusCancelled status once
+// their token is cancelled. The token must outlive them, and may be cancelled from any goroutine.
+
+func CancellationTokenNew() *CancellationToken {
+	return (*CancellationToken)(C.cancellation_token_new())
+}
+
+func CancellationTokenCancel(token *CancellationToken) {
+	C.cancellation_token_cancel((*C.CancellationToken_t)(token))
+}
+
+func CancellationTokenIsCancelled(token *CancellationToken) bool {
+	return bool(C.cancellation_token_is_cancelled((*C.CancellationToken_t)(token)))
+}
+
+func GenerateWindowPoStCancellable(randomness *ByteArray32, replicas SliceRefPrivateReplicaInfo, proverId *ByteArray32, token *CancellationToken) ([]PoStProofGo, []uint64, error) {
+	resp := (*resultGenerateWindowPoSt)(C.generate_window_post_cancellable(
+		(*C.uint8_32_array_t)(randomness),
+		(C.slice_ref_PrivateReplicaInfo_t)(replicas),
+		(*C.uint8_32_array_t)(proverId),
+		(*C.CancellationToken_t)(token)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		faults := (SliceBoxedUint64)(resp.value.faulty_sectors).copy()
+		return nil, faults, err
+	}
+
+	proofs := (SliceBoxedPoStProof)(resp.value.proofs).copy()
+	return proofs, []uint64{}, nil
+}
+
+func SealCommitPhase2Cancellable(sealCommitPhase1Output SliceRefUint8, sectorId uint64, proverId *ByteArray32, token *CancellationToken) ([]byte, error) {
+	resp := (*resultSliceBoxedUint8)(C.seal_commit_phase2_cancellable(
+		(C.slice_ref_uint8_t)(sealCommitPhase1Output),
+		C.uint64_t(sectorId),
+		(*C.uint8_32_array_t)(proverId),
+		(*C.CancellationToken_t)(token)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func EmptySectorUpdateEncodeIntoCancellable(registeredProof RegisteredUpdateProof, newReplicaPath SliceRefUint8, newCacheDirPath SliceRefUint8, sectorKeyPath SliceRefUint8, sectorKeyCacheDirPath SliceRefUint8, stagedDataPath SliceRefUint8, pieces SliceRefPublicPieceInfo, token *CancellationToken) ([]byte, []byte, error) {
+	resp := (*resultEmptySectorUpdateEncodeInto)(C.empty_sector_update_encode_into_cancellable(
+		(C.RegisteredUpdateProof_t)(registeredProof),
+		(C.slice_ref_uint8_t)(newReplicaPath),
+		(C.slice_ref_uint8_t)(newCacheDirPath),
+		(C.slice_ref_uint8_t)(sectorKeyPath),
+		(C.slice_ref_uint8_t)(sectorKeyCacheDirPath),
+		(C.slice_ref_uint8_t)(stagedDataPath),
+		(C.slice_ref_PublicPieceInfo_t)(pieces),
+		(*C.CancellationToken_t)(token)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, nil, err
+	}
+	return (*ByteArray32)(&resp.value.comm_r_new).copy(), (*ByteArray32)(&resp.value.comm_d_new).copy(), nil
+}
@@ -13,6 +13,37 @@ const (
 	FCPResponseStatusUnclassifiedError = C.F_C_P_RESPONSE_STATUS_UNCLASSIFIED_ERROR
 	FCPResponseStatusCallerError       = C.F_C_P_RESPONSE_STATUS_CALLER_ERROR
 	FCPResponseStatusReceiverError     = C.F_C_P_RESPONSE_STATUS_RECEIVER_ERROR
+	FCPResponseStatusCancelled         = C.F_C_P_RESPONSE_STATUS_CANCELLED
+)
+
+const (
+	ProofsErrorMissingFile        = C.PROOFS_ERROR_MISSING_FILE
+	ProofsErrorCorruptCache       = C.PROOFS_ERROR_CORRUPT_CACHE
+	ProofsErrorInvalidProofType   = C.PROOFS_ERROR_INVALID_PROOF_TYPE
+	ProofsErrorInvalidInputLength = C.PROOFS_ERROR_INVALID_INPUT_LENGTH
+	ProofsErrorFaultySectors      = C.PROOFS_ERROR_FAULTY_SECTORS
+	ProofsErrorMissingParameters  = C.PROOFS_ERROR_MISSING_PARAMETERS
+)
+
+const (
+	BLSErrorInvalidSignatureEncoding = C.B_L_S_ERROR_INVALID_SIGNATURE_ENCODING
+	BLSErrorInvalidPublicKeyEncoding = C.B_L_S_ERROR_INVALID_PUBLIC_KEY_ENCODING
+	BLSErrorInvalidDigestEncoding    = C.B_L_S_ERROR_INVALID_DIGEST_ENCODING
+	BLSErrorPointNotInSubgroup       = C.B_L_S_ERROR_POINT_NOT_IN_SUBGROUP
+	BLSErrorInvalidPrivateKey        = C.B_L_S_ERROR_INVALID_PRIVATE_KEY
+	BLSErrorCountMismatch            = C.B_L_S_ERROR_COUNT_MISMATCH
+	BLSErrorMalformedMessages        = C.B_L_S_ERROR_MALFORMED_MESSAGES
+	BLSErrorEmptyInput               = C.B_L_S_ERROR_EMPTY_INPUT
+	BLSErrorInvalidThreshold         = C.B_L_S_ERROR_INVALID_THRESHOLD
+	BLSErrorInvalidShareIndex        = C.B_L_S_ERROR_INVALID_SHARE_INDEX
+	BLSErrorInvalidSeed              = C.B_L_S_ERROR_INVALID_SEED
+	BLSErrorInvalidDerivationPath    = C.B_L_S_ERROR_INVALID_DERIVATION_PATH
+	BLSErrorIdentityPoint            = C.B_L_S_ERROR_IDENTITY_POINT
+	BLSErrorInvalidKeystore          = C.B_L_S_ERROR_INVALID_KEYSTORE
+	BLSErrorInvalidPassword          = C.B_L_S_ERROR_INVALID_PASSWORD
+	BLSErrorInvalidMessageEncoding   = C.B_L_S_ERROR_INVALID_MESSAGE_ENCODING
+	BLSErrorDuplicateMessage         = C.B_L_S_ERROR_DUPLICATE_MESSAGE
+	BLSErrorSenderMismatch           = C.B_L_S_ERROR_SENDER_MISMATCH
 )
 
 const (
@@ -68,3 +99,27 @@ const (
 	RegisteredUpdateProofStackedDrg32GiBV1  = C.REGISTERED_UPDATE_PROOF_STACKED_DRG32_GI_B_V1
 	RegisteredUpdateProofStackedDrg64GiBV1  = C.REGISTERED_UPDATE_PROOF_STACKED_DRG64_GI_B_V1
 )
+
+const (
+	DrandSchemeChained     = C.DRAND_SCHEME_CHAINED
+	DrandSchemeUnchained   = C.DRAND_SCHEME_UNCHAINED
+	DrandSchemeUnchainedG1 = C.DRAND_SCHEME_UNCHAINED_G1
+)
+
+const (
+	BLSKeystoreKdfScrypt = C.B_L_S_KEYSTORE_KDF_SCRYPT
+	BLSKeystoreKdfPbkdf2 = C.B_L_S_KEYSTORE_KDF_PBKDF2
+)
+
+const (
+	SealProgressKindLayerDone           = C.SEAL_PROGRESS_KIND_LAYER_DONE
+	SealProgressKindTreeBuildPercentage = C.SEAL_PROGRESS_KIND_TREE_BUILD_PERCENTAGE
+	SealProgressKindPartitionProofDone  = C.SEAL_PROGRESS_KIND_PARTITION_PROOF_DONE
+)
+
+const (
+	SectorCacheCheckStatusOk      = C.SECTOR_CACHE_CHECK_STATUS_OK
+	SectorCacheCheckStatusMissing = C.SECTOR_CACHE_CHECK_STATUS_MISSING
+	SectorCacheCheckStatusInvalid = C.SECTOR_CACHE_CHECK_STATUS_INVALID
+	SectorCacheCheckStatusSkipped = C.SECTOR_CACHE_CHECK_STATUS_SKIPPED
+)
@@ -0,0 +1,26 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+
+func DelegatedRecoverAddress(transaction SliceRefUint8, signature SliceRefUint8) ([]byte, error) {
+	resp := (*resultDelegatedAddress)(C.delegated_recover_address((C.slice_ref_uint8_t)(transaction),
+		(C.slice_ref_uint8_t)(signature)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func DelegatedVerify(transaction SliceRefUint8, signature SliceRefUint8, expectedAddress SliceRefUint8) bool {
+	resp := C.delegated_verify((C.slice_ref_uint8_t)(transaction),
+		(C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(expectedAddress))
+	return bool(resp)
+}
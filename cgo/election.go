@@ -0,0 +1,34 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+
+func ElectionComputeWinCount(vrfProof SliceRefUint8, minerPower SliceRefUint8, networkPower SliceRefUint8, expectedLeaders uint64) (uint64, error) {
+	resp := (*resultElectionWinCount)(C.election_compute_win_count((C.slice_ref_uint8_t)(vrfProof),
+		(C.slice_ref_uint8_t)(minerPower),
+		(C.slice_ref_uint8_t)(networkPower),
+		C.uint64_t(expectedLeaders)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return 0, err
+	}
+	return uint64(resp.value), nil
+}
+
+func ElectionVerifyVrf(workerPublicKey SliceRefUint8, vrfBase SliceRefUint8, vrfProof SliceRefUint8) bool {
+	resp := C.election_verify_vrf((C.slice_ref_uint8_t)(workerPublicKey),
+		(C.slice_ref_uint8_t)(vrfBase),
+		(C.slice_ref_uint8_t)(vrfProof))
+	return bool(resp)
+}
+
+func ElectionVrfDigest(vrfProof SliceRefUint8) *[32]byte {
+	resp := (*ByteArray32)(C.election_vrf_digest((C.slice_ref_uint8_t)(vrfProof)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
@@ -6,6 +6,7 @@ typedef const uint8_t* buf_t;
 */
 import "C"
 import (
+	rtcgo "runtime/cgo"
 	"unsafe"
 
 	"github.com/filecoin-project/go-address"
@@ -145,3 +146,19 @@ func cgo_extern_get_tipset_cid(
 	copy(out, k.Bytes())
 	return 0
 }
+
+//export cgo_seal_progress
+func cgo_seal_progress(handle C.uintptr_t, kind C.int32_t, done C.uint64_t, total C.uint64_t) {
+	defer func() {
+		if rerr := recover(); rerr != nil {
+			logPanic(rerr)
+		}
+	}()
+
+	progress := rtcgo.Handle(handle).Value().(func(SealProgress))
+	progress(SealProgress{
+		Kind:  SealProgressKind(kind),
+		Done:  uint64(done),
+		Total: uint64(total),
+	})
+}
@@ -213,7 +213,31 @@ func AsByteArray32(goSlice []byte) ByteArray32 {
 	return ary
 }
 
-// CheckErr returns `nil` if the `code` indicates success and an error otherwise.
+// ProofsErr is the error of a failed proofs call whose cause is known.
+type ProofsErr struct {
+	Status FCPResponseStatus
+	Code   ProofsError
+	Msg    string
+}
+
+func (e *ProofsErr) Error() string {
+	return e.Msg
+}
+
+// BLSErr is the error of a failed BLS call.
+type BLSErr struct {
+	Status FCPResponseStatus
+	Code   BLSError
+	Msg    string
+}
+
+func (e *BLSErr) Error() string {
+	return e.Msg
+}
+
+// CheckErr returns `nil` if the `code` indicates success and an error otherwise. The error of a
+// proofs call is a `*ProofsErr` and the one of a BLS call a `*BLSErr` if the response has an
+// error code.
 func CheckErr(resp result) error {
 	if resp == nil {
 		return errors.New("nil result from Filecoin FFI")
@@ -221,6 +245,24 @@ func CheckErr(resp result) error {
 	if resp.statusCode() == FCPResponseStatusNoError {
 		return nil
 	}
+	if proofsResp, ok := resp.(proofsResult); ok {
+		if code := proofsResp.errorCode(); code != 0 {
+			return &ProofsErr{
+				Status: resp.statusCode(),
+				Code:   code,
+				Msg:    string(resp.errorMsg().slice()),
+			}
+		}
+	}
+	if blsResp, ok := resp.(blsResult); ok {
+		if code := blsResp.errorCode(); code != 0 {
+			return &BLSErr{
+				Status: resp.statusCode(),
+				Code:   code,
+				Msg:    string(resp.errorMsg().slice()),
+			}
+		}
+	}
 
 	return errors.New(string(resp.errorMsg().slice()))
 }
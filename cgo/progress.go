@@ -0,0 +1,91 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+
+extern void cgo_seal_progress(uintptr_t handle, int32_t kind, uint64_t done, uint64_t total);
+
+static void seal_progress_call(void *env, SealProgress_t progress) {
+	cgo_seal_progress((uintptr_t)env, progress.kind, progress.done, progress.total);
+}
+
+static RefDynFnMut1_void_SealProgress_t seal_progress_callback(uintptr_t handle) {
+	RefDynFnMut1_void_SealProgress_t callback = { (void *)handle, seal_progress_call };
+	return callback;
+}
+*/
+import "C"
+import rtcgo "runtime/cgo"
+
+// The progress callbacks are called from a thread of the proofs, but never concurrently and not
+// after the call returned, so the handle is only deleted once it did.
+
+func GenerateSDRWithProgress(registeredProof RegisteredSealProof, outDir SliceRefUint8, replicaID *ByteArray32, progress func(SealProgress)) error {
+	handle := rtcgo.NewHandle(progress)
+	defer handle.Delete()
+
+	resp := (*resultVoid)(C.generate_sdr_with_progress(
+		(C.RegisteredSealProof_t)(registeredProof),
+		(C.slice_ref_uint8_t)(outDir),
+		(*C.uint8_32_array_t)(replicaID),
+		C.seal_progress_callback(C.uintptr_t(handle))))
+	defer resp.destroy()
+
+	return CheckErr(resp)
+}
+
+func SealPreCommitPhase1WithProgress(registeredProof RegisteredSealProof, cacheDirPath SliceRefUint8, stagedSectorPath SliceRefUint8, sealedSectorPath SliceRefUint8, sectorId uint64, proverId *ByteArray32, ticket *ByteArray32, pieces SliceRefPublicPieceInfo, progress func(SealProgress)) ([]byte, error) {
+	handle := rtcgo.NewHandle(progress)
+	defer handle.Delete()
+
+	resp := (*resultSliceBoxedUint8)(C.seal_pre_commit_phase1_with_progress(
+		(C.RegisteredSealProof_t)(registeredProof),
+		(C.slice_ref_uint8_t)(cacheDirPath),
+		(C.slice_ref_uint8_t)(stagedSectorPath),
+		(C.slice_ref_uint8_t)(sealedSectorPath),
+		C.uint64_t(sectorId),
+		(*C.uint8_32_array_t)(proverId),
+		(*C.uint8_32_array_t)(ticket),
+		(C.slice_ref_PublicPieceInfo_t)(pieces),
+		C.seal_progress_callback(C.uintptr_t(handle))))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func SealPreCommitPhase2WithProgress(sealPreCommitPhase1Output SliceRefUint8, cacheDirPath SliceRefUint8, sealedSectorPath SliceRefUint8, progress func(SealProgress)) ([]byte, []byte, error) {
+	handle := rtcgo.NewHandle(progress)
+	defer handle.Delete()
+
+	resp := (*resultSealPreCommitPhase2)(C.seal_pre_commit_phase2_with_progress(
+		(C.slice_ref_uint8_t)(sealPreCommitPhase1Output),
+		(C.slice_ref_uint8_t)(cacheDirPath),
+		(C.slice_ref_uint8_t)(sealedSectorPath),
+		C.seal_progress_callback(C.uintptr_t(handle))))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, nil, err
+	}
+	return (*ByteArray32)(&resp.value.comm_r).copy(), (*ByteArray32)(&resp.value.comm_d).copy(), nil
+}
+
+func SealCommitPhase2WithProgress(sealCommitPhase1Output SliceRefUint8, sectorId uint64, proverId *ByteArray32, progress func(SealProgress)) ([]byte, error) {
+	handle := rtcgo.NewHandle(progress)
+	defer handle.Delete()
+
+	resp := (*resultSliceBoxedUint8)(C.seal_commit_phase2_with_progress(
+		(C.slice_ref_uint8_t)(sealCommitPhase1Output),
+		C.uint64_t(sectorId),
+		(*C.uint8_32_array_t)(proverId),
+		C.seal_progress_callback(C.uintptr_t(handle))))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
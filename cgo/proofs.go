@@ -644,3 +644,102 @@ func EmptySectorUpdateDecodeFromRange(registeredProof RegisteredUpdateProof, com
 
 	return CheckErr(resp)
 }
+
+func CheckSectorCache(registeredProof RegisteredSealProof, cacheDirPath SliceRefUint8, replicaPath SliceRefUint8, commR *ByteArray32) (SectorCacheCheckGo, error) {
+	resp := (*resultSectorCacheCheck)(C.check_sector_cache(
+		(C.RegisteredSealProof_t)(registeredProof),
+		(C.slice_ref_uint8_t)(cacheDirPath),
+		(C.slice_ref_uint8_t)(replicaPath),
+		(*C.uint8_32_array_t)(commR)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return SectorCacheCheckGo{}, err
+	}
+	return resp.copy(), nil
+}
+
+func PieceCommitmentHasherCreate(unpaddedSizeHint uint64) (*PieceCommitmentHasher, error) {
+	resp := (*resultPieceCommitmentHasher)(C.piece_commitment_hasher_create(C.uint64_t(unpaddedSizeHint)))
+	// take out the pointer from the result to ensure it doesn't get freed
+	hasher := (*PieceCommitmentHasher)(resp.value)
+	resp.value = nil
+	defer resp.destroy()
+
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+
+	return hasher, nil
+}
+
+func PieceCommitmentHasherWrite(hasher *PieceCommitmentHasher, data SliceRefUint8) {
+	C.piece_commitment_hasher_write((*C.PieceCommitmentHasher_t)(hasher), (C.slice_ref_uint8_t)(data))
+}
+
+// PieceCommitmentHasherFinalize returns the piece commitment and the padded piece size. The hasher
+// is consumed, it must not be used or destroyed afterwards.
+func PieceCommitmentHasherFinalize(hasher *PieceCommitmentHasher) ([]byte, uint64, error) {
+	resp := (*resultPieceCommitment)(C.piece_commitment_hasher_finalize((*C.PieceCommitmentHasher_t)(hasher)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, 0, err
+	}
+
+	return (*ByteArray32)(&resp.value.comm_p).copy(), uint64(resp.value.padded_size), nil
+}
+
+// Fr32PadBytes returns `padded` with the range starting at `unpaddedOffset` overwritten by the
+// padded `unpadded`, extended as needed.
+func Fr32PadBytes(padded SliceRefUint8, unpadded SliceRefUint8, unpaddedOffset uint64) ([]byte, error) {
+	resp := (*resultSliceBoxedUint8)(C.fr32_pad_bytes(
+		(C.slice_ref_uint8_t)(padded),
+		(C.slice_ref_uint8_t)(unpadded),
+		C.uint64_t(unpaddedOffset)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func Fr32PadRange(srcFdRaw int32, dstFdRaw int32, unpaddedOffset uint64, unpaddedLen uint64) error {
+	resp := (*resultVoid)(C.fr32_pad_range(
+		C.int32_t(srcFdRaw),
+		C.int32_t(dstFdRaw),
+		C.uint64_t(unpaddedOffset),
+		C.uint64_t(unpaddedLen)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return err
+	}
+
+	return nil
+}
+
+func Fr32UnpadBytes(padded SliceRefUint8, unpaddedOffset uint64, unpaddedLen uint64) ([]byte, error) {
+	resp := (*resultSliceBoxedUint8)(C.fr32_unpad_bytes(
+		(C.slice_ref_uint8_t)(padded),
+		C.uint64_t(unpaddedOffset),
+		C.uint64_t(unpaddedLen)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func Fr32UnpadRange(srcFdRaw int32, dstFdRaw int32, unpaddedOffset uint64, unpaddedLen uint64) error {
+	resp := (*resultVoid)(C.fr32_unpad_range(
+		C.int32_t(srcFdRaw),
+		C.int32_t(dstFdRaw),
+		C.uint64_t(unpaddedOffset),
+		C.uint64_t(unpaddedLen)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return err
+	}
+
+	return nil
+}
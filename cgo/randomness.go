@@ -0,0 +1,18 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+
+func DrawRandomness(base SliceRefUint8, personalization int64, round int64, entropy SliceRefUint8) *[32]byte {
+	resp := (*ByteArray32)(C.draw_randomness((C.slice_ref_uint8_t)(base),
+		C.int64_t(personalization),
+		C.int64_t(round),
+		(C.slice_ref_uint8_t)(entropy)))
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
@@ -0,0 +1,51 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+
+func SecpPrivateKeyGenerate() *[32]byte {
+	resp := (*ByteArray32)(C.secp_private_key_generate())
+	defer resp.destroy()
+	return resp.copyAsArray()
+}
+
+func SecpPrivateKeyPublicKey(rawPrivateKey SliceRefUint8) ([]byte, error) {
+	resp := (*resultSecpBytes)(C.secp_private_key_public_key((C.slice_ref_uint8_t)(rawPrivateKey)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func SecpPrivateKeySign(rawPrivateKey SliceRefUint8, message SliceRefUint8) ([]byte, error) {
+	resp := (*resultSecpBytes)(C.secp_private_key_sign((C.slice_ref_uint8_t)(rawPrivateKey),
+		(C.slice_ref_uint8_t)(message)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func SecpRecoverPublicKey(signature SliceRefUint8, message SliceRefUint8) ([]byte, error) {
+	resp := (*resultSecpBytes)(C.secp_recover_public_key((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(message)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+	return (SliceBoxedUint8)(resp.value).copy(), nil
+}
+
+func SecpVerify(signature SliceRefUint8, message SliceRefUint8, publicKey SliceRefUint8) bool {
+	resp := C.secp_verify((C.slice_ref_uint8_t)(signature),
+		(C.slice_ref_uint8_t)(message),
+		(C.slice_ref_uint8_t)(publicKey))
+	return bool(resp)
+}
@@ -13,10 +13,21 @@ import (
 
 type FCPResponseStatus int64
 
+// ProofsError is the cause of a failed proofs call, see the `ProofsError` constants. It is 0 if
+// the cause is unknown.
+type ProofsError int32
+
+// BLSError is the cause of a failed BLS call, see the `BLSError` constants.
+type BLSError int32
+
 type RegisteredSealProof C.RegisteredSealProof_t
 type RegisteredAggregationProof C.RegisteredAggregationProof_t
 type RegisteredPoStProof C.RegisteredPoStProof_t
 type RegisteredUpdateProof C.RegisteredUpdateProof_t
+type DrandScheme C.DrandScheme_t
+type SealProgressKind C.SealProgressKind_t
+type SectorCacheCheckStatus C.SectorCacheCheckStatus_t
+type BLSKeystoreKdf C.BLSKeystoreKdf_t
 
 // FVM types moved to types_fvm.go behind build tag
 
@@ -44,10 +55,20 @@ type SliceBoxedUint64 C.struct_slice_boxed_uint64
 type SliceBoxedSliceBoxedUint8 C.slice_boxed_slice_boxed_uint8_t
 type SliceBoxedSliceBoxedUint64 C.slice_boxed_slice_boxed_uint64_t
 type SliceBoxedUint8 C.struct_slice_boxed_uint8
+type SliceBoxedBool C.slice_boxed_bool_t
 
 type ByteArray32 C.uint8_32_array_t
 type ByteArray48 C.uint8_48_array_t
 type ByteArray96 C.uint8_96_array_t
+type ByteArray100 C.uint8_100_array_t
+
+type BLSBatchVerify C.BLSBatchVerify_t
+type BLSKeyHandle C.InnerBLSKeyHandle_t
+type BLSPublicKeyCacheStats C.BLSPublicKeyCacheStats_t
+type BLSAggregator C.InnerBLSAggregator_t
+type BLSBlockVerify C.BLSBlockVerify_t
+type CancellationToken C.CancellationToken_t
+type PieceCommitmentHasher C.PieceCommitmentHasher_t
 
 // FVM types moved to types_fvm.go behind build tag
 
@@ -68,6 +89,18 @@ type resultEmptySectorUpdateEncodeInto C.Result_EmptySectorUpdateEncodeInto_t
 type resultGenerateFallbackSectorChallenges C.Result_GenerateFallbackSectorChallenges_t
 type resultGenerateSingleWindowPoStWithVanilla C.Result_GenerateSingleWindowPoStWithVanilla_t
 type resultPoStProof C.Result_PoStProof_t
+type resultSectorCacheCheck C.Result_SectorCacheCheck_t
+type resultSliceBoxedBool C.Result_slice_boxed_bool_t
+type resultPieceCommitmentHasher C.Result_PieceCommitmentHasher_ptr_t
+type resultPieceCommitment C.Result_PieceCommitment_t
+
+type resultBLSBytes C.Result_slice_boxed_uint8_t
+type resultBLSBool C.Result_bool_t
+type resultBLSVoid C.Result_void_t
+type resultBLSKeyHandle C.Result_InnerBLSKeyHandle_ptr_t
+type resultSecpBytes C.Result_slice_boxed_uint8_t
+type resultDelegatedAddress C.Result_slice_boxed_uint8_t
+type resultElectionWinCount C.Result_uint64_t
 
 // FVM types moved to types_fvm.go behind build tag
 
@@ -77,6 +110,18 @@ type result interface {
 	destroy()
 }
 
+// proofsResult is the result of a proofs call, which classifies errors with a `ProofsError`.
+type proofsResult interface {
+	result
+	errorCode() ProofsError
+}
+
+// blsResult is the result of a BLS call, which classifies errors with a `BLSError`.
+type blsResult interface {
+	result
+	errorCode() BLSError
+}
+
 // PartitionSnarkProofGo is a go allocated version of `PartitionSnarkProof`.
 type PartitionSnarkProofGo struct {
 	RegisteredProof RegisteredPoStProof
@@ -89,6 +134,32 @@ type PoStProofGo struct {
 	Proof           []byte
 }
 
+// SectorCacheCheckGo is a go allocated version of `SectorCacheCheck`.
+type SectorCacheCheckGo struct {
+	Replica        SectorCacheCheckStatus
+	PAux           SectorCacheCheckStatus
+	TAux           SectorCacheCheckStatus
+	TreeRLastFiles SectorCacheCheckStatus
+	TreeRLastRoot  SectorCacheCheckStatus
+	CommR          SectorCacheCheckStatus
+}
+
+// SealProgress is a progress event of a sealing operation, `Done` of `Total` units of work of
+// `Kind`.
+type SealProgress struct {
+	Kind  SealProgressKind
+	Done  uint64
+	Total uint64
+}
+
+// BLSPublicKeyCacheStatsGo is a go allocated version of `BLSPublicKeyCacheStats`.
+type BLSPublicKeyCacheStatsGo struct {
+	Capacity uint64
+	Size     uint64
+	Hits     uint64
+	Misses   uint64
+}
+
 // FvmMachineExecuteResponse is a go allocated version of `FvmMachineExecuteResponse`.
 type FvmMachineExecuteResponseGo struct {
 	ExitCode             uint64
@@ -139,6 +210,10 @@ func (ptr *resultBool) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultBool) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultBool) destroy() {
 	if ptr != nil {
 		C.destroy_verify_seal_response((*C.Result_bool_t)(ptr))
@@ -177,6 +252,26 @@ func (ptr *ByteArray96) copyAsArray() *[96]byte {
 	return &res
 }
 
+func (ptr *ByteArray100) destroy() {
+	if ptr != nil {
+		C.destroy_box_bls_partial_signature((*C.uint8_100_array_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr ByteArray100) slice() []byte {
+	return unsafe.Slice((*byte)(unsafe.Pointer(&ptr.idx[0])), 100)
+}
+
+func (ptr *ByteArray100) copyAsArray() *[100]byte {
+	if ptr == nil {
+		return nil
+	}
+	var res [100]byte
+	copy(res[:], ptr.slice())
+	return &res
+}
+
 func (ptr ByteArray48) slice() []byte {
 	return unsafe.Slice((*byte)(unsafe.Pointer(&ptr.idx[0])), 48)
 }
@@ -225,6 +320,36 @@ func (ptr *ByteArray32) destroy() {
 	}
 }
 
+func (ptr *BLSBatchVerify) destroy() {
+	if ptr != nil {
+		C.destroy_box_bls_batch_verify((*C.BLSBatchVerify_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *BLSBlockVerify) destroy() {
+	if ptr != nil {
+		C.destroy_box_bls_block_verify((*C.BLSBlockVerify_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *BLSPublicKeyCacheStats) destroy() {
+	if ptr != nil {
+		C.destroy_box_bls_public_key_cache_stats((*C.BLSPublicKeyCacheStats_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *BLSPublicKeyCacheStats) copy() BLSPublicKeyCacheStatsGo {
+	return BLSPublicKeyCacheStatsGo{
+		Capacity: uint64(ptr.capacity),
+		Size:     uint64(ptr.size),
+		Hits:     uint64(ptr.hits),
+		Misses:   uint64(ptr.misses),
+	}
+}
+
 func (ptr *resultGeneratePieceCommitment) statusCode() FCPResponseStatus {
 	return FCPResponseStatus(ptr.status_code)
 }
@@ -233,6 +358,10 @@ func (ptr *resultGeneratePieceCommitment) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultGeneratePieceCommitment) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultGeneratePieceCommitment) destroy() {
 	if ptr != nil {
 		C.destroy_generate_piece_commitment_response((*C.Result_GeneratePieceCommitment_t)(ptr))
@@ -248,6 +377,10 @@ func (ptr *resultByteArray32) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultByteArray32) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultByteArray32) destroy() {
 	if ptr != nil {
 		C.destroy_generate_data_commitment_response((*C.Result_uint8_32_array_t)(ptr))
@@ -263,6 +396,10 @@ func (ptr *resultWriteWithAlignment) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultWriteWithAlignment) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultWriteWithAlignment) destroy() {
 	if ptr != nil {
 		C.destroy_write_with_alignment_response((*C.Result_WriteWithAlignment_t)(ptr))
@@ -278,6 +415,10 @@ func (ptr *resultWriteWithoutAlignment) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultWriteWithoutAlignment) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultWriteWithoutAlignment) destroy() {
 	if ptr != nil {
 		C.destroy_write_without_alignment_response((*C.Result_WriteWithoutAlignment_t)(ptr))
@@ -293,6 +434,10 @@ func (ptr *resultSliceBoxedUint8) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultSliceBoxedUint8) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultSliceBoxedUint8) destroy() {
 	if ptr != nil {
 		C.destroy_seal_pre_commit_phase1_response((*C.Result_slice_boxed_uint8_t)(ptr))
@@ -308,6 +453,10 @@ func (ptr *resultSealPreCommitPhase2) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultSealPreCommitPhase2) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultSealPreCommitPhase2) destroy() {
 	if ptr != nil {
 		C.destroy_seal_pre_commit_phase2_response((*C.Result_SealPreCommitPhase2_t)(ptr))
@@ -323,6 +472,10 @@ func (ptr *resultVoid) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultVoid) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultVoid) destroy() {
 	if ptr != nil {
 		C.destroy_unseal_range_response((*C.Result_void_t)(ptr))
@@ -357,6 +510,10 @@ func (ptr *resultSliceBoxedUint64) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultSliceBoxedUint64) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultSliceBoxedUint64) destroy() {
 	if ptr != nil {
 		C.destroy_generate_winning_post_sector_challenge((*C.Result_slice_boxed_uint64_t)(ptr))
@@ -402,6 +559,10 @@ func (ptr *resultSliceBoxedPoStProof) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultSliceBoxedPoStProof) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultSliceBoxedPoStProof) destroy() {
 	if ptr != nil {
 		C.destroy_generate_winning_post_response((*C.Result_slice_boxed_PoStProof_t)(ptr))
@@ -417,6 +578,10 @@ func (ptr *resultGenerateWindowPoSt) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultGenerateWindowPoSt) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultGenerateWindowPoSt) destroy() {
 	if ptr != nil {
 		C.destroy_generate_window_post_response((*C.Result_GenerateWindowPoSt_t)(ptr))
@@ -470,6 +635,10 @@ func (ptr *resultSliceBoxedSliceBoxedUint8) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultSliceBoxedSliceBoxedUint8) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultSliceBoxedSliceBoxedUint8) destroy() {
 	if ptr != nil {
 		C.destroy_generate_empty_sector_update_partition_proof_response((*C.Result_slice_boxed_slice_boxed_uint8_t)(ptr))
@@ -485,6 +654,10 @@ func (ptr *resultUint) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultUint) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultUint) destroy() {
 	if ptr != nil {
 		C.destroy_get_num_partition_for_fallback_post_response((*C.Result_size_t)(ptr))
@@ -500,6 +673,10 @@ func (ptr *resultEmptySectorUpdateEncodeInto) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultEmptySectorUpdateEncodeInto) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultEmptySectorUpdateEncodeInto) destroy() {
 	if ptr != nil {
 		C.destroy_empty_sector_update_encode_into_response((*C.Result_EmptySectorUpdateEncodeInto_t)(ptr))
@@ -538,6 +715,10 @@ func (ptr *resultGenerateFallbackSectorChallenges) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultGenerateFallbackSectorChallenges) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultGenerateFallbackSectorChallenges) destroy() {
 	if ptr != nil {
 		C.destroy_generate_fallback_sector_challenges_response((*C.Result_GenerateFallbackSectorChallenges_t)(ptr))
@@ -560,6 +741,10 @@ func (ptr *resultGenerateSingleWindowPoStWithVanilla) errorMsg() *SliceBoxedUint
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultGenerateSingleWindowPoStWithVanilla) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultGenerateSingleWindowPoStWithVanilla) destroy() {
 	if ptr != nil {
 		C.destroy_generate_single_window_post_with_vanilla_response((*C.Result_GenerateSingleWindowPoStWithVanilla_t)(ptr))
@@ -575,6 +760,10 @@ func (ptr *resultPoStProof) errorMsg() *SliceBoxedUint8 {
 	return (*SliceBoxedUint8)(&ptr.error_msg)
 }
 
+func (ptr *resultPoStProof) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
 func (ptr *resultPoStProof) destroy() {
 	if ptr != nil {
 		C.destroy_merge_window_post_partition_proofs_response((*C.Result_PoStProof_t)(ptr))
@@ -582,6 +771,261 @@ func (ptr *resultPoStProof) destroy() {
 	}
 }
 
+func (ptr *resultSectorCacheCheck) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultSectorCacheCheck) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultSectorCacheCheck) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
+func (ptr *resultSectorCacheCheck) destroy() {
+	if ptr != nil {
+		C.destroy_check_sector_cache_response((*C.Result_SectorCacheCheck_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultSectorCacheCheck) copy() SectorCacheCheckGo {
+	return SectorCacheCheckGo{
+		Replica:        SectorCacheCheckStatus(ptr.value.replica),
+		PAux:           SectorCacheCheckStatus(ptr.value.p_aux),
+		TAux:           SectorCacheCheckStatus(ptr.value.t_aux),
+		TreeRLastFiles: SectorCacheCheckStatus(ptr.value.tree_r_last_files),
+		TreeRLastRoot:  SectorCacheCheckStatus(ptr.value.tree_r_last_root),
+		CommR:          SectorCacheCheckStatus(ptr.value.comm_r),
+	}
+}
+
+func (ptr SliceBoxedBool) slice() []bool {
+	if ptr.ptr == nil {
+		return nil
+	}
+	return unsafe.Slice((*bool)(unsafe.Pointer(ptr.ptr)), int(ptr.len))
+}
+
+func (ptr SliceBoxedBool) copy() []bool {
+	if ptr.ptr == nil {
+		return nil
+	} else if ptr.len == 0 {
+		return []bool{}
+	}
+
+	res := make([]bool, int(ptr.len))
+	copy(res, ptr.slice())
+	return res
+}
+
+func (ptr *resultSliceBoxedBool) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultSliceBoxedBool) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultSliceBoxedBool) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
+func (ptr *resultSliceBoxedBool) destroy() {
+	if ptr != nil {
+		C.destroy_verify_single_vanilla_proofs_response((*C.Result_slice_boxed_bool_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultBLSBytes) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultBLSBytes) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultBLSBytes) errorCode() BLSError {
+	return BLSError(ptr.error_code)
+}
+
+func (ptr *resultBLSBytes) destroy() {
+	if ptr != nil {
+		C.destroy_bls_signature_response((*C.Result_slice_boxed_uint8_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultBLSBool) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultBLSBool) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultBLSBool) errorCode() BLSError {
+	return BLSError(ptr.error_code)
+}
+
+func (ptr *resultBLSBool) destroy() {
+	if ptr != nil {
+		C.destroy_bls_verify_response((*C.Result_bool_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultBLSVoid) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultBLSVoid) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultBLSVoid) errorCode() BLSError {
+	return BLSError(ptr.error_code)
+}
+
+func (ptr *resultBLSVoid) destroy() {
+	if ptr != nil {
+		C.destroy_bls_validate_response((*C.Result_void_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultBLSKeyHandle) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultBLSKeyHandle) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultBLSKeyHandle) errorCode() BLSError {
+	return BLSError(ptr.error_code)
+}
+
+func (ptr *resultBLSKeyHandle) destroy() {
+	if ptr != nil {
+		C.destroy_bls_key_handle_response((*C.Result_InnerBLSKeyHandle_ptr_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *BLSKeyHandle) Destroy() {
+	if ptr != nil {
+		C.drop_bls_key_handle((*C.InnerBLSKeyHandle_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *BLSAggregator) Destroy() {
+	if ptr != nil {
+		C.drop_bls_aggregator((*C.InnerBLSAggregator_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *PieceCommitmentHasher) Destroy() {
+	if ptr != nil {
+		C.destroy_piece_commitment_hasher((*C.PieceCommitmentHasher_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultPieceCommitmentHasher) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultPieceCommitmentHasher) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultPieceCommitmentHasher) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
+func (ptr *resultPieceCommitmentHasher) destroy() {
+	if ptr != nil {
+		C.destroy_piece_commitment_hasher_response((*C.Result_PieceCommitmentHasher_ptr_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultPieceCommitment) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultPieceCommitment) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultPieceCommitment) errorCode() ProofsError {
+	return ProofsError(ptr.error_code)
+}
+
+func (ptr *resultPieceCommitment) destroy() {
+	if ptr != nil {
+		C.destroy_piece_commitment_response((*C.Result_PieceCommitment_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *CancellationToken) Destroy() {
+	if ptr != nil {
+		C.destroy_cancellation_token((*C.CancellationToken_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultSecpBytes) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultSecpBytes) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultSecpBytes) destroy() {
+	if ptr != nil {
+		C.destroy_secp_signature_response((*C.Result_slice_boxed_uint8_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultDelegatedAddress) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultDelegatedAddress) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultDelegatedAddress) destroy() {
+	if ptr != nil {
+		C.destroy_delegated_address_response((*C.Result_slice_boxed_uint8_t)(ptr))
+		ptr = nil
+	}
+}
+
+func (ptr *resultElectionWinCount) statusCode() FCPResponseStatus {
+	return FCPResponseStatus(ptr.status_code)
+}
+
+func (ptr *resultElectionWinCount) errorMsg() *SliceBoxedUint8 {
+	return (*SliceBoxedUint8)(&ptr.error_msg)
+}
+
+func (ptr *resultElectionWinCount) destroy() {
+	if ptr != nil {
+		C.destroy_election_win_count_response((*C.Result_uint64_t)(ptr))
+		ptr = nil
+	}
+}
+
 func (ptr *SliceBoxedUint8) Destroy() {
 	if ptr.ptr != nil {
 		C.destroy_boxed_slice(*(*C.struct_slice_boxed_uint8)(ptr))
@@ -0,0 +1,65 @@
+package cgo
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/..
+#cgo pkg-config: ${SRCDIR}/../filcrypto.pc
+#include "../filcrypto.h"
+#include <stdlib.h>
+*/
+import "C"
+import (
+	"runtime"
+	"unsafe"
+)
+
+func VerifySingleVanillaProof(registeredProof RegisteredPoStProof, sectorId uint64, commR *ByteArray32, challenges SliceRefUint64, vanillaProof SliceRefUint8) (bool, error) {
+	resp := (*resultBool)(C.verify_single_vanilla_proof(
+		(C.RegisteredPoStProof_t)(registeredProof),
+		C.uint64_t(sectorId),
+		(*C.uint8_32_array_t)(commR),
+		(C.slice_ref_uint64_t)(challenges),
+		(C.slice_ref_uint8_t)(vanillaProof)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return false, err
+	}
+
+	return bool(resp.value), nil
+}
+
+// VerifySingleVanillaProofs verifies the vanilla proofs of many sectors, `challenges` and
+// `vanillaProofs` are given in the order of `replicas`. The challenges are passed to the proofs
+// without copying them, so they are pinned for the duration of the call.
+func VerifySingleVanillaProofs(replicas SliceRefPublicReplicaInfo, challenges [][]uint64, vanillaProofs SliceRefSliceBoxedUint8) ([]bool, error) {
+	var pinner runtime.Pinner
+	defer pinner.Unpin()
+
+	// the outer slice needs an element to point at even if it is empty
+	boxedChallenges := make([]C.slice_boxed_uint64_t, len(challenges), len(challenges)+1)
+	for i, sectorChallenges := range challenges {
+		if len(sectorChallenges) == 0 {
+			// can't point at element 0 of an empty slice
+			sectorChallenges = make([]uint64, 0, 1)
+		}
+		ptr := unsafe.SliceData(sectorChallenges)
+		pinner.Pin(ptr)
+		boxedChallenges[i] = C.slice_boxed_uint64_t{
+			ptr: (*C.uint64_t)(unsafe.Pointer(ptr)),
+			len: C.size_t(len(sectorChallenges)),
+		}
+	}
+
+	resp := (*resultSliceBoxedBool)(C.verify_single_vanilla_proofs(
+		(C.slice_ref_PublicReplicaInfo_t)(replicas),
+		C.slice_ref_slice_boxed_uint64_t{
+			ptr: unsafe.SliceData(boxedChallenges[:cap(boxedChallenges)]),
+			len: C.size_t(len(challenges)),
+		},
+		(C.slice_ref_slice_boxed_uint8_t)(vanillaProofs)))
+	defer resp.destroy()
+	if err := CheckErr(resp); err != nil {
+		return nil, err
+	}
+
+	return (SliceBoxedBool)(resp.value).copy(), nil
+}
@@ -0,0 +1,163 @@
+use blstrs::G2Projective;
+use group::{Curve, Group};
+use safer_ffi::prelude::*;
+
+use super::api::{decode_signature, BLSSignature};
+use super::types::*;
+use crate::destructor;
+
+impl Default for InnerBLSAggregator {
+    fn default() -> Self {
+        InnerBLSAggregator {
+            aggregate: G2Projective::identity(),
+            count: 0,
+        }
+    }
+}
+
+/// Create an empty aggregator
+#[ffi_export]
+pub fn bls_aggregator_new() -> repr_c::Box<InnerBLSAggregator> {
+    Box::<InnerBLSAggregator>::default().into()
+}
+
+/// Add a signature to the aggregate
+///
+/// # Arguments
+///
+/// * `aggregator` - aggregator handle
+/// * `signature`  - signature byte array (SIGNATURE_BYTES long)
+///
+/// Returns `false` and leaves the aggregate untouched if the signature is invalid.
+#[ffi_export]
+pub fn bls_aggregator_add(
+    aggregator: &mut InnerBLSAggregator,
+    signature: c_slice::Ref<'_, u8>,
+) -> bool {
+    let Ok(signature) = decode_signature(&signature) else {
+        return false;
+    };
+
+    aggregator.aggregate += G2Projective::from(signature);
+    aggregator.count += 1;
+    true
+}
+
+/// Remove a signature, that was added before, from the aggregate
+///
+/// # Arguments
+///
+/// * `aggregator` - aggregator handle
+/// * `signature`  - signature byte array (SIGNATURE_BYTES long)
+///
+/// Returns `false` and leaves the aggregate untouched if the signature is invalid or the
+/// aggregate is empty. Removing a signature that was never added is not detected, and leaves
+/// an aggregate that won't verify.
+#[ffi_export]
+pub fn bls_aggregator_subtract(
+    aggregator: &mut InnerBLSAggregator,
+    signature: c_slice::Ref<'_, u8>,
+) -> bool {
+    if aggregator.count == 0 {
+        return false;
+    }
+    let Ok(signature) = decode_signature(&signature) else {
+        return false;
+    };
+
+    aggregator.aggregate -= G2Projective::from(signature);
+    aggregator.count -= 1;
+    true
+}
+
+/// Return the number of signatures in the aggregate
+#[ffi_export]
+pub fn bls_aggregator_count(aggregator: &InnerBLSAggregator) -> u64 {
+    aggregator.count
+}
+
+/// Return the aggregate of the signatures added so far
+///
+/// The aggregator is left untouched and can be used further. The result is the same as calling
+/// `aggregate` with the signatures in the aggregate.
+///
+/// Returns `None` if the aggregate is empty.
+#[ffi_export]
+pub fn bls_aggregator_finalize(
+    aggregator: &InnerBLSAggregator,
+) -> Option<repr_c::Box<BLSSignature>> {
+    if aggregator.count == 0 {
+        return None;
+    }
+    let signature: BLSSignature = aggregator.aggregate.to_affine().to_compressed();
+
+    Some(Box::new(signature).into())
+}
+
+/// Remove all signatures from the aggregate
+#[ffi_export]
+pub fn bls_aggregator_reset(aggregator: &mut InnerBLSAggregator) {
+    *aggregator = InnerBLSAggregator::default();
+}
+
+destructor!(drop_bls_aggregator, InnerBLSAggregator);
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::{aggregate, private_key_generate, private_key_sign};
+    use super::*;
+
+    #[test]
+    fn running_aggregate() {
+        let signatures: Vec<_> = (0..4)
+            .map(|i: u8| {
+                private_key_sign(private_key_generate()[..].into(), [i][..].into()).unwrap()
+            })
+            .collect();
+        let aggregate_of = |indices: &[usize]| {
+            let flattened: Vec<u8> = indices.iter().flat_map(|i| *signatures[*i]).collect();
+            *aggregate(flattened[..].into()).unwrap()
+        };
+
+        let mut aggregator = bls_aggregator_new();
+        assert!(bls_aggregator_finalize(&aggregator).is_none());
+        assert!(!bls_aggregator_subtract(
+            &mut aggregator,
+            signatures[0][..].into()
+        ));
+
+        for signature in &signatures {
+            assert!(bls_aggregator_add(&mut aggregator, signature[..].into()));
+        }
+        assert_eq!(bls_aggregator_count(&aggregator), 4);
+        assert_eq!(
+            *bls_aggregator_finalize(&aggregator).unwrap(),
+            aggregate_of(&[0, 1, 2, 3])
+        );
+
+        assert!(bls_aggregator_subtract(
+            &mut aggregator,
+            signatures[1][..].into()
+        ));
+        assert_eq!(bls_aggregator_count(&aggregator), 3);
+        assert_eq!(
+            *bls_aggregator_finalize(&aggregator).unwrap(),
+            aggregate_of(&[0, 2, 3])
+        );
+
+        // invalid signatures leave the aggregate untouched
+        assert!(!bls_aggregator_add(
+            &mut aggregator,
+            signatures[0][1..].into()
+        ));
+        assert_eq!(bls_aggregator_count(&aggregator), 3);
+        assert_eq!(
+            *bls_aggregator_finalize(&aggregator).unwrap(),
+            aggregate_of(&[0, 2, 3])
+        );
+
+        bls_aggregator_reset(&mut aggregator);
+        assert_eq!(bls_aggregator_count(&aggregator), 0);
+        assert!(bls_aggregator_finalize(&aggregator).is_none());
+    }
+}
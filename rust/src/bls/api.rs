@@ -1,27 +1,55 @@
 use bls_signatures::{
     aggregate as aggregate_sig, hash as hash_sig, verify as verify_sig,
-    verify_messages as verify_messages_sig, Error, PrivateKey, PublicKey, Serialize, Signature,
+    verify_messages as verify_messages_sig, PrivateKey, PublicKey, Serialize, Signature,
+};
+use blstrs::{
+    Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, MillerLoopResult, Scalar,
 };
-use blstrs::{G2Affine, G2Projective};
 use group::prime::PrimeCurveAffine;
-use group::GroupEncoding;
+use group::{Curve, Group, GroupEncoding};
+use pairing::{MillerLoopResult as _, MultiMillerLoop};
 
 use rand::rngs::OsRng;
-use rand::SeedableRng;
+use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
 use rayon::prelude::*;
 use safer_ffi::prelude::*;
 
+use super::types::*;
+use crate::destructor;
+use crate::util::types::{catch_panic_response_raw, FCPResponseStatus};
+
 pub const SIGNATURE_BYTES: usize = 96;
 pub const PRIVATE_KEY_BYTES: usize = 32;
 pub const PUBLIC_KEY_BYTES: usize = 48;
 pub const DIGEST_BYTES: usize = 96;
+pub const G1_UNCOMPRESSED_BYTES: usize = 96;
+pub const G2_UNCOMPRESSED_BYTES: usize = 192;
 
 pub type BLSSignature = [u8; SIGNATURE_BYTES];
 pub type BLSPrivateKey = [u8; PRIVATE_KEY_BYTES];
 pub type BLSPublicKey = [u8; PUBLIC_KEY_BYTES];
 pub type BLSDigest = [u8; DIGEST_BYTES];
 
+/// Domain separation tag used for proofs of possession, as defined by the proof of possession
+/// ciphersuite of the IETF BLS signature draft.
+pub const POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain separation tag of the Filecoin ciphersuite, used by [`hash`], [`private_key_sign`] and
+/// [`hash_verify`].
+pub const FILECOIN_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Outcome of a [`batch_verify`] call.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BLSBatchVerify {
+    /// `true` if every entry of the batch carries a valid signature.
+    pub valid: bool,
+    /// Index of the first entry that failed verification. Only meaningful if `valid` is `false`.
+    pub first_invalid_index: u64,
+}
+
 /// Unwraps or returns the passed in value.
 macro_rules! try_ffi {
     ($res:expr, $val:expr) => {{
@@ -52,6 +80,11 @@ fn destroy_box_bls_signature(ptr: repr_c::Box<BLSSignature>) {
     drop(ptr);
 }
 
+#[ffi_export]
+fn destroy_box_bls_batch_verify(ptr: repr_c::Box<BLSBatchVerify>) {
+    drop(ptr);
+}
+
 /// Compute the digest of a message
 ///
 /// # Arguments
@@ -75,18 +108,9 @@ pub fn hash(message: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSDigest> {
 /// Returns `None` on error. Result must be freed using `destroy_aggregate_response`.
 #[ffi_export]
 pub fn aggregate(flattened_signatures: c_slice::Ref<'_, u8>) -> Option<repr_c::Box<BLSSignature>> {
-    // prep request
-    let signatures = try_ffi!(
-        flattened_signatures
-            .par_chunks(SIGNATURE_BYTES)
-            .map(|item| { Signature::from_bytes(item) })
-            .collect::<Result<Vec<_>, _>>(),
-        None
-    );
+    let aggregated = try_ffi!(aggregate_inner(&flattened_signatures), None);
 
     let mut signature: [u8; SIGNATURE_BYTES] = [0; SIGNATURE_BYTES];
-
-    let aggregated = try_ffi!(aggregate_sig(&signatures), None);
     aggregated
         .write_bytes(&mut signature.as_mut())
         .expect("preallocated");
@@ -94,6 +118,101 @@ pub fn aggregate(flattened_signatures: c_slice::Ref<'_, u8>) -> Option<repr_c::B
     Some(Box::new(signature).into())
 }
 
+fn aggregate_inner(flattened_signatures: &[u8]) -> Result<Signature, BLSError> {
+    if !flattened_signatures.len().is_multiple_of(SIGNATURE_BYTES) {
+        return Err(BLSError::InvalidSignatureEncoding);
+    }
+
+    let signatures = flattened_signatures
+        .par_chunks(SIGNATURE_BYTES)
+        .map(decode_signature)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    aggregate_sig(&signatures).map_err(|_| BLSError::EmptyInput)
+}
+
+/// Aggregate public keys together into a new public key
+///
+/// The public keys must have been checked with [`public_key_verify_possession`] before, otherwise
+/// the aggregate is vulnerable to rogue-key attacks.
+///
+/// # Arguments
+///
+/// * `flattened_public_keys` - byte array containing public keys
+///
+/// Returns `None` on error. Result must be freed using `destroy_box_bls_public_key`.
+#[ffi_export]
+pub fn aggregate_public_keys(
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> Option<repr_c::Box<BLSPublicKey>> {
+    let public_keys = try_ffi!(parse_public_keys(&flattened_public_keys), None);
+    let aggregated = try_ffi!(aggregate_public_keys_inner(&public_keys), None);
+
+    let mut raw_public_key: [u8; PUBLIC_KEY_BYTES] = [0; PUBLIC_KEY_BYTES];
+    aggregated
+        .write_bytes(&mut raw_public_key.as_mut())
+        .expect("preallocated");
+
+    Some(Box::new(raw_public_key).into())
+}
+
+fn parse_public_keys(flattened_public_keys: &[u8]) -> Result<Vec<PublicKey>, BLSError> {
+    if !flattened_public_keys.len().is_multiple_of(PUBLIC_KEY_BYTES) {
+        return Err(BLSError::InvalidPublicKeyEncoding);
+    }
+
+    super::cache::decode_public_keys(flattened_public_keys).map_err(|(error, _)| error)
+}
+
+fn aggregate_public_keys_inner(public_keys: &[PublicKey]) -> Result<PublicKey, BLSError> {
+    if public_keys.is_empty() {
+        return Err(BLSError::EmptyInput);
+    }
+
+    let aggregated = public_keys
+        .par_iter()
+        .map(|public_key| G1Projective::from(*public_key))
+        .reduce(G1Projective::identity, |acc, cur| acc + cur);
+
+    Ok(aggregated.into())
+}
+
+/// Verify that a signature is the aggregated signature of a single message signed by all the
+/// given public keys
+///
+/// Only two pairings are needed, independent of the number of signers. The public keys must
+/// have been checked with [`public_key_verify_possession`] before, otherwise the check is
+/// vulnerable to rogue-key attacks.
+///
+/// # Arguments
+///
+/// * `signature`             - signature byte array (SIGNATURE_BYTES long)
+/// * `message`               - message byte array
+/// * `flattened_public_keys` - byte array containing public keys
+#[ffi_export]
+pub fn fast_aggregate_verify(
+    signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> bool {
+    matches!(
+        fast_aggregate_verify_inner(&signature, &message, &flattened_public_keys),
+        Ok(true)
+    )
+}
+
+fn fast_aggregate_verify_inner(
+    signature: &[u8],
+    message: &[u8],
+    flattened_public_keys: &[u8],
+) -> Result<bool, BLSError> {
+    let signature = decode_signature(signature)?;
+    let public_keys = parse_public_keys(flattened_public_keys)?;
+    let aggregated = aggregate_public_keys_inner(&public_keys)?;
+
+    Ok(aggregated.verify(signature, message))
+}
+
 /// Verify that a signature is the aggregated signature of hashes - pubkeys
 ///
 /// # Arguments
@@ -107,43 +226,46 @@ pub fn verify(
     flattened_digests: c_slice::Ref<'_, u8>,
     flattened_public_keys: c_slice::Ref<'_, u8>,
 ) -> bool {
+    matches!(
+        verify_inner(&signature, &flattened_digests, &flattened_public_keys),
+        Ok(true)
+    )
+}
+
+fn verify_inner(
+    signature: &[u8],
+    flattened_digests: &[u8],
+    flattened_public_keys: &[u8],
+) -> Result<bool, BLSError> {
     // prep request
-    let signature = try_ffi!(Signature::from_bytes(&signature), false);
+    let signature = decode_signature(signature)?;
 
     if !flattened_digests.len().is_multiple_of(DIGEST_BYTES) {
-        return false;
+        return Err(BLSError::InvalidDigestEncoding);
     }
     if !flattened_public_keys.len().is_multiple_of(PUBLIC_KEY_BYTES) {
-        return false;
+        return Err(BLSError::InvalidPublicKeyEncoding);
     }
 
     if flattened_digests.len() / DIGEST_BYTES != flattened_public_keys.len() / PUBLIC_KEY_BYTES {
-        return false;
+        return Err(BLSError::CountMismatch);
+    }
+    if flattened_digests.is_empty() {
+        return Err(BLSError::EmptyInput);
     }
 
-    let digests: Vec<_> = try_ffi!(
-        flattened_digests
-            .par_chunks(DIGEST_BYTES)
-            .map(|item: &[u8]| {
-                let mut digest = [0u8; DIGEST_BYTES];
-                digest.as_mut().copy_from_slice(item);
-
-                let affine: Option<G2Affine> = Option::from(G2Affine::from_compressed(&digest));
-                affine.map(Into::into).ok_or(Error::CurveDecode)
-            })
-            .collect::<Result<Vec<G2Projective>, Error>>(),
-        false
-    );
+    let digests: Vec<G2Projective> = flattened_digests
+        .par_chunks(DIGEST_BYTES)
+        .map(decode_digest)
+        .collect::<Result<_, _>>()?;
 
-    let public_keys: Vec<_> = try_ffi!(
-        flattened_public_keys
-            .par_chunks(PUBLIC_KEY_BYTES)
-            .map(|item| { PublicKey::from_bytes(item) })
-            .collect::<Result<_, _>>(),
-        false
-    );
+    let public_keys = parse_public_keys(flattened_public_keys)?;
 
-    verify_sig(&signature, digests.as_slice(), public_keys.as_slice())
+    Ok(verify_sig(
+        &signature,
+        digests.as_slice(),
+        public_keys.as_slice(),
+    ))
 }
 
 /// Verify that a signature is the aggregated signature of the hashed messages
@@ -162,30 +284,188 @@ pub fn hash_verify(
     message_sizes: c_slice::Ref<'_, libc::size_t>,
     flattened_public_keys: c_slice::Ref<'_, u8>,
 ) -> bool {
+    matches!(
+        hash_verify_inner(
+            &signature,
+            &flattened_messages,
+            &message_sizes,
+            &flattened_public_keys
+        ),
+        Ok(true)
+    )
+}
+
+fn hash_verify_inner(
+    signature: &[u8],
+    flattened_messages: &[u8],
+    message_sizes: &[libc::size_t],
+    flattened_public_keys: &[u8],
+) -> Result<bool, BLSError> {
     // prep request
-    let signature = try_ffi!(Signature::from_bytes(&signature), false);
+    let signature = decode_signature(signature)?;
 
     // split the flattened message array into slices of individual messages to be hashed
+    let messages = split_messages(flattened_messages, message_sizes).map_err(|(error, _)| error)?;
+
+    let public_keys = parse_public_keys(flattened_public_keys)?;
+
+    if messages.len() != public_keys.len() {
+        return Err(BLSError::CountMismatch);
+    }
+    if messages.is_empty() {
+        return Err(BLSError::EmptyInput);
+    }
+
+    Ok(verify_messages_sig(&signature, &messages, &public_keys))
+}
+
+/// Splits the flattened message array into slices of individual messages. Fails with the index
+/// of the first message that overruns the array.
+pub(super) fn split_messages<'a>(
+    flattened_messages: &'a [u8],
+    message_sizes: &[libc::size_t],
+) -> Result<Vec<&'a [u8]>, (BLSError, usize)> {
     let mut messages: Vec<&[u8]> = Vec::with_capacity(message_sizes.len());
-    let mut offset = 0;
-    for chunk_size in message_sizes.iter() {
-        messages.push(&flattened_messages[offset..offset + *chunk_size]);
-        offset += *chunk_size
+    let mut offset = 0usize;
+    for (i, chunk_size) in message_sizes.iter().enumerate() {
+        let end = offset
+            .checked_add(*chunk_size)
+            .filter(|end| *end <= flattened_messages.len())
+            .ok_or((BLSError::MalformedMessages, i))?;
+        messages.push(&flattened_messages[offset..end]);
+        offset = end;
     }
 
-    if !flattened_public_keys.len().is_multiple_of(PUBLIC_KEY_BYTES) {
+    Ok(messages)
+}
+
+/// Verify many independent signatures at once, each over its own message and public key.
+///
+/// The entries are checked together using a random linear combination, so a valid batch costs
+/// a single multi-pairing. If the batch fails, the entries are verified one by one to locate the
+/// first invalid one.
+///
+/// # Arguments
+///
+/// * `flattened_signatures`  - byte array containing signatures
+/// * `flattened_messages`    - byte array containing the concatenated messages
+/// * `message_sizes`         - array containing the lengths of the messages
+/// * `flattened_public_keys` - byte array containing public keys
+///
+/// Entries which cannot be decoded, or which are missing one of their three parts, count as
+/// invalid. An empty batch is reported as invalid at index `0`.
+#[ffi_export]
+pub fn batch_verify(
+    flattened_signatures: c_slice::Ref<'_, u8>,
+    flattened_messages: c_slice::Ref<'_, u8>,
+    message_sizes: c_slice::Ref<'_, libc::size_t>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSBatchVerify> {
+    let total = flattened_signatures
+        .len()
+        .div_ceil(SIGNATURE_BYTES)
+        .max(flattened_public_keys.len().div_ceil(PUBLIC_KEY_BYTES))
+        .max(message_sizes.len());
+
+    // everything past the first undecodable or incomplete entry is ignored
+    let signatures: Vec<Signature> = flattened_signatures
+        .par_chunks(SIGNATURE_BYTES)
+        .map(decode_signature)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map_while(Result::ok)
+        .collect();
+    let messages = match split_messages(&flattened_messages, &message_sizes) {
+        Ok(messages) => messages,
+        Err((_, index)) => split_messages(&flattened_messages, &message_sizes[..index])
+            .expect("messages before the first overrun fit"),
+    };
+    let public_keys = match super::cache::decode_public_keys(&flattened_public_keys) {
+        Ok(public_keys) => public_keys,
+        Err((_, index)) => {
+            super::cache::decode_public_keys(&flattened_public_keys[..index * PUBLIC_KEY_BYTES])
+                .expect("keys before the first invalid one decode")
+        }
+    };
+
+    let decoded = signatures.len().min(public_keys.len()).min(messages.len());
+    let signatures = &signatures[..decoded];
+    let public_keys = &public_keys[..decoded];
+
+    let first_invalid = match first_invalid_index(signatures, &messages[..decoded], public_keys) {
+        Some(index) => Some(index),
+        None if decoded < total || total == 0 => Some(decoded),
+        None => None,
+    };
+
+    Box::new(BLSBatchVerify {
+        valid: first_invalid.is_none(),
+        first_invalid_index: first_invalid.unwrap_or_default() as u64,
+    })
+    .into()
+}
+
+/// Returns the index of the first entry whose signature does not verify, if any.
+fn first_invalid_index(
+    signatures: &[Signature],
+    messages: &[&[u8]],
+    public_keys: &[PublicKey],
+) -> Option<usize> {
+    if verify_batch_randomized(signatures, messages, public_keys) {
+        return None;
+    }
+
+    signatures
+        .par_iter()
+        .zip(messages.par_iter())
+        .zip(public_keys.par_iter())
+        .position_first(|((signature, message), public_key)| {
+            !public_key.verify(*signature, message)
+        })
+}
+
+/// Checks `e(g1, \sum_{i} r_i * sig_i) == \prod_{i} e(r_i * pk_i, hash_i)` for random 64 bit
+/// scalars `r_i`, which holds for all valid entries and fails otherwise with overwhelming
+/// probability.
+fn verify_batch_randomized(
+    signatures: &[Signature],
+    messages: &[&[u8]],
+    public_keys: &[PublicKey],
+) -> bool {
+    if signatures.is_empty() {
+        return true;
+    }
+
+    // identity keys are never valid, not even inside a batch
+    if public_keys
+        .par_iter()
+        .any(|public_key| bool::from(public_key.as_affine().is_identity()))
+    {
         return false;
     }
 
-    let public_keys: Vec<_> = try_ffi!(
-        flattened_public_keys
-            .par_chunks(PUBLIC_KEY_BYTES)
-            .map(|item| { PublicKey::from_bytes(item) })
-            .collect::<Result<_, _>>(),
-        false
-    );
+    let scalars: Vec<Scalar> = (0..signatures.len())
+        .map(|_| Scalar::from(OsRng.next_u64().max(1)))
+        .collect();
+
+    let points: Vec<G2Projective> = signatures.iter().map(|s| (*s).into()).collect();
+    let aggregated = G2Projective::multi_exp(&points, &scalars).to_affine();
 
-    verify_messages_sig(&signature, &messages, &public_keys)
+    let ml = messages
+        .par_iter()
+        .zip(public_keys.par_iter())
+        .zip(scalars.par_iter())
+        .map(|((message, public_key), scalar)| {
+            let public_key = (G1Projective::from(*public_key) * scalar).to_affine();
+            let hash = G2Prepared::from(hash_sig(message).to_affine());
+            Bls12::multi_miller_loop(&[(&public_key, &hash)])
+        })
+        .reduce(MillerLoopResult::default, |acc, cur| acc + cur);
+
+    let g1_neg = -G1Affine::generator();
+    let ml = ml + Bls12::multi_miller_loop(&[(&g1_neg, &G2Prepared::from(aggregated))]);
+
+    ml.final_exponentiation() == Gt::identity()
 }
 
 /// Generate a new private key
@@ -231,7 +511,7 @@ pub fn private_key_sign(
     raw_private_key: c_slice::Ref<'_, u8>,
     message: c_slice::Ref<'_, u8>,
 ) -> Option<repr_c::Box<BLSSignature>> {
-    let private_key = try_ffi!(PrivateKey::from_bytes(&raw_private_key), None);
+    let private_key = try_ffi!(decode_private_key(&raw_private_key), None);
 
     let mut raw_signature: [u8; SIGNATURE_BYTES] = [0; SIGNATURE_BYTES];
     PrivateKey::sign(&private_key, &message[..])
@@ -252,7 +532,7 @@ pub fn private_key_sign(
 pub fn private_key_public_key(
     raw_private_key: c_slice::Ref<'_, u8>,
 ) -> Option<repr_c::Box<BLSPublicKey>> {
-    let private_key = try_ffi!(PrivateKey::from_bytes(&raw_private_key), None);
+    let private_key = try_ffi!(decode_private_key(&raw_private_key), None);
 
     let mut raw_public_key: [u8; PUBLIC_KEY_BYTES] = [0; PUBLIC_KEY_BYTES];
     private_key
@@ -263,6 +543,568 @@ pub fn private_key_public_key(
     Some(Box::new(raw_public_key).into())
 }
 
+/// Generate a proof of possession for the public key of a private key
+///
+/// The proof is a signature over the compressed public key, hashed with [`POP_DST`]. Verifying
+/// it before aggregating public keys protects against rogue-key attacks.
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+///
+/// Returns `None` when passed invalid arguments.
+#[ffi_export]
+pub fn private_key_prove_possession(
+    raw_private_key: c_slice::Ref<'_, u8>,
+) -> Option<repr_c::Box<BLSSignature>> {
+    let private_key = try_ffi!(decode_private_key(&raw_private_key), None);
+    let proof = prove_possession_inner(&private_key);
+
+    let mut raw_proof: [u8; SIGNATURE_BYTES] = [0; SIGNATURE_BYTES];
+    proof
+        .write_bytes(&mut raw_proof.as_mut())
+        .expect("preallocated");
+
+    Some(Box::new(raw_proof).into())
+}
+
+/// Verify a proof of possession for a public key
+///
+/// # Arguments
+///
+/// * `raw_public_key` - public key byte array (PUBLIC_KEY_BYTES long)
+/// * `raw_proof`      - proof of possession byte array (SIGNATURE_BYTES long)
+#[ffi_export]
+pub fn public_key_verify_possession(
+    raw_public_key: c_slice::Ref<'_, u8>,
+    raw_proof: c_slice::Ref<'_, u8>,
+) -> bool {
+    matches!(
+        verify_possession_inner(&raw_public_key, &raw_proof),
+        Ok(true)
+    )
+}
+
+fn prove_possession_inner(private_key: &PrivateKey) -> Signature {
+    let public_key = private_key.public_key().as_bytes();
+    sign_with_dst(private_key, &public_key, POP_DST)
+}
+
+fn verify_possession_inner(raw_public_key: &[u8], raw_proof: &[u8]) -> Result<bool, BLSError> {
+    let public_key = decode_public_key(raw_public_key)?;
+    let proof = decode_signature(raw_proof)?;
+
+    Ok(verify_with_dst(
+        &proof,
+        raw_public_key,
+        &public_key,
+        POP_DST,
+    ))
+}
+
+/// Hashes `message` to G2 using the given domain separation tag.
+fn hash_with_dst(message: &[u8], dst: &[u8]) -> G2Projective {
+    G2Projective::hash_to_curve(message, dst, &[])
+}
+
+/// Signs `message` after hashing it with the given domain separation tag.
+fn sign_with_dst(private_key: &PrivateKey, message: &[u8], dst: &[u8]) -> Signature {
+    (hash_with_dst(message, dst) * Scalar::from(*private_key)).into()
+}
+
+/// Checks `e(pk, hash(message)) == e(g1, signature)`, with `message` hashed using the given
+/// domain separation tag. Identity public keys are rejected.
+fn verify_with_dst(
+    signature: &Signature,
+    message: &[u8],
+    public_key: &PublicKey,
+    dst: &[u8],
+) -> bool {
+    let public_key = public_key.as_affine();
+    if bool::from(public_key.is_identity()) {
+        return false;
+    }
+
+    let hash = G2Prepared::from(hash_with_dst(message, dst).to_affine());
+    let g1_neg = -G1Affine::generator();
+    let signature = G2Prepared::from(G2Affine::from(*signature));
+
+    Bls12::multi_miller_loop(&[(&public_key, &hash), (&g1_neg, &signature)]).final_exponentiation()
+        == Gt::identity()
+}
+
+/// Compute the digest of a message, hashed with the given domain separation tag
+///
+/// # Arguments
+///
+/// * `message` - reference to a message byte array
+/// * `dst`     - domain separation tag, must not be empty
+///
+/// Returns `None` when passed an empty domain separation tag.
+#[ffi_export]
+pub fn hash_dst(
+    message: c_slice::Ref<'_, u8>,
+    dst: c_slice::Ref<'_, u8>,
+) -> Option<repr_c::Box<BLSDigest>> {
+    if dst.is_empty() {
+        return None;
+    }
+    let digest = hash_with_dst(&message, &dst).to_affine().to_compressed();
+
+    Some(Box::new(digest).into())
+}
+
+/// Sign a message, hashed with the given domain separation tag, with a private key and return
+/// the signature
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+/// * `message`         - message byte array
+/// * `dst`             - domain separation tag, must not be empty
+///
+/// Returns `None` when passed invalid arguments.
+#[ffi_export]
+pub fn private_key_sign_dst(
+    raw_private_key: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    dst: c_slice::Ref<'_, u8>,
+) -> Option<repr_c::Box<BLSSignature>> {
+    if dst.is_empty() {
+        return None;
+    }
+    let private_key = try_ffi!(decode_private_key(&raw_private_key), None);
+
+    let mut raw_signature: [u8; SIGNATURE_BYTES] = [0; SIGNATURE_BYTES];
+    sign_with_dst(&private_key, &message, &dst)
+        .write_bytes(&mut raw_signature.as_mut())
+        .expect("preallocated");
+
+    Some(Box::new(raw_signature).into())
+}
+
+/// Verify a signature over a message, hashed with the given domain separation tag
+///
+/// # Arguments
+///
+/// * `signature`  - signature byte array (SIGNATURE_BYTES long)
+/// * `message`    - message byte array
+/// * `public_key` - public key byte array (PUBLIC_KEY_BYTES long)
+/// * `dst`        - domain separation tag, must not be empty
+#[ffi_export]
+pub fn verify_dst(
+    signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    public_key: c_slice::Ref<'_, u8>,
+    dst: c_slice::Ref<'_, u8>,
+) -> bool {
+    if dst.is_empty() {
+        return false;
+    }
+    let signature = try_ffi!(decode_signature(&signature), false);
+    let public_key = try_ffi!(decode_public_key(&public_key), false);
+
+    verify_with_dst(&signature, &message, &public_key, &dst)
+}
+
+/// Same as [`hash_verify`], but with the messages hashed with the given domain separation tag
+///
+/// As with [`hash_verify`], the messages must be distinct.
+///
+/// # Arguments
+///
+/// * `signature`             - signature byte array (SIGNATURE_BYTES long)
+/// * `flattened_messages`    - byte array containing the concatenated messages
+/// * `message_sizes`         - array containing the lengths of the messages
+/// * `flattened_public_keys` - byte array containing public keys
+/// * `dst`                   - domain separation tag, must not be empty
+#[ffi_export]
+pub fn hash_verify_dst(
+    signature: c_slice::Ref<'_, u8>,
+    flattened_messages: c_slice::Ref<'_, u8>,
+    message_sizes: c_slice::Ref<'_, libc::size_t>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+    dst: c_slice::Ref<'_, u8>,
+) -> bool {
+    matches!(
+        hash_verify_dst_inner(
+            &signature,
+            &flattened_messages,
+            &message_sizes,
+            &flattened_public_keys,
+            &dst,
+        ),
+        Ok(true)
+    )
+}
+
+fn hash_verify_dst_inner(
+    signature: &[u8],
+    flattened_messages: &[u8],
+    message_sizes: &[libc::size_t],
+    flattened_public_keys: &[u8],
+    dst: &[u8],
+) -> Result<bool, BLSError> {
+    let signature = decode_signature(signature)?;
+    let messages = split_messages(flattened_messages, message_sizes).map_err(|(error, _)| error)?;
+    let public_keys = parse_public_keys(flattened_public_keys)?;
+
+    if messages.len() != public_keys.len() {
+        return Err(BLSError::CountMismatch);
+    }
+    if messages.is_empty() {
+        return Err(BLSError::EmptyInput);
+    }
+    if dst.is_empty() {
+        return Ok(false);
+    }
+
+    let mut seen = std::collections::HashSet::with_capacity(messages.len());
+    if !messages.iter().all(|message| seen.insert(*message)) {
+        return Ok(false);
+    }
+    if public_keys
+        .iter()
+        .any(|public_key| bool::from(public_key.as_affine().is_identity()))
+    {
+        return Ok(false);
+    }
+
+    let ml = messages
+        .par_iter()
+        .zip(public_keys.par_iter())
+        .map(|(message, public_key)| {
+            let hash = G2Prepared::from(hash_with_dst(message, dst).to_affine());
+            Bls12::multi_miller_loop(&[(&public_key.as_affine(), &hash)])
+        })
+        .reduce(MillerLoopResult::default, |acc, cur| acc + cur);
+
+    let g1_neg = -G1Affine::generator();
+    let signature = G2Prepared::from(G2Affine::from(signature));
+    let ml = ml + Bls12::multi_miller_loop(&[(&g1_neg, &signature)]);
+
+    Ok(ml.final_exponentiation() == Gt::identity())
+}
+
+/// Same as [`aggregate`], but reports the cause of a failure.
+#[ffi_export]
+pub fn aggregate_result(
+    flattened_signatures: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSSignatureResponse> {
+    catch_panic_response_raw("aggregate_result", || {
+        to_response(
+            aggregate_inner(&flattened_signatures).map(|s| s.as_bytes().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Same as [`aggregate_public_keys`], but reports the cause of a failure.
+#[ffi_export]
+pub fn aggregate_public_keys_result(
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSPublicKeyResponse> {
+    catch_panic_response_raw("aggregate_public_keys_result", || {
+        to_response(
+            parse_public_keys(&flattened_public_keys)
+                .and_then(|public_keys| aggregate_public_keys_inner(&public_keys))
+                .map(|p| p.as_bytes().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Same as [`fast_aggregate_verify`], but reports the cause of a failure.
+#[ffi_export]
+pub fn fast_aggregate_verify_result(
+    signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSVerifyResponse> {
+    catch_panic_response_raw("fast_aggregate_verify_result", || {
+        to_response(fast_aggregate_verify_inner(
+            &signature,
+            &message,
+            &flattened_public_keys,
+        ))
+    })
+}
+
+/// Same as [`verify`], but reports the cause of a failure.
+#[ffi_export]
+pub fn verify_result(
+    signature: c_slice::Ref<'_, u8>,
+    flattened_digests: c_slice::Ref<'_, u8>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSVerifyResponse> {
+    catch_panic_response_raw("verify_result", || {
+        to_response(verify_inner(
+            &signature,
+            &flattened_digests,
+            &flattened_public_keys,
+        ))
+    })
+}
+
+/// Same as [`hash_verify`], but reports the cause of a failure.
+#[ffi_export]
+pub fn hash_verify_result(
+    signature: c_slice::Ref<'_, u8>,
+    flattened_messages: c_slice::Ref<'_, u8>,
+    message_sizes: c_slice::Ref<'_, libc::size_t>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSVerifyResponse> {
+    catch_panic_response_raw("hash_verify_result", || {
+        to_response(hash_verify_inner(
+            &signature,
+            &flattened_messages,
+            &message_sizes,
+            &flattened_public_keys,
+        ))
+    })
+}
+
+/// Same as [`private_key_sign`], but reports the cause of a failure.
+#[ffi_export]
+pub fn private_key_sign_result(
+    raw_private_key: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSSignatureResponse> {
+    catch_panic_response_raw("private_key_sign_result", || {
+        to_response(decode_private_key(&raw_private_key).map(|private_key| {
+            private_key
+                .sign(&message[..])
+                .as_bytes()
+                .into_boxed_slice()
+                .into()
+        }))
+    })
+}
+
+/// Same as [`private_key_public_key`], but reports the cause of a failure.
+#[ffi_export]
+pub fn private_key_public_key_result(
+    raw_private_key: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSPublicKeyResponse> {
+    catch_panic_response_raw("private_key_public_key_result", || {
+        to_response(decode_private_key(&raw_private_key).map(|private_key| {
+            private_key
+                .public_key()
+                .as_bytes()
+                .into_boxed_slice()
+                .into()
+        }))
+    })
+}
+
+/// Same as [`private_key_prove_possession`], but reports the cause of a failure.
+#[ffi_export]
+pub fn private_key_prove_possession_result(
+    raw_private_key: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSSignatureResponse> {
+    catch_panic_response_raw("private_key_prove_possession_result", || {
+        to_response(decode_private_key(&raw_private_key).map(|private_key| {
+            prove_possession_inner(&private_key)
+                .as_bytes()
+                .into_boxed_slice()
+                .into()
+        }))
+    })
+}
+
+/// Same as [`public_key_verify_possession`], but reports the cause of a failure.
+#[ffi_export]
+pub fn public_key_verify_possession_result(
+    raw_public_key: c_slice::Ref<'_, u8>,
+    raw_proof: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSVerifyResponse> {
+    catch_panic_response_raw("public_key_verify_possession_result", || {
+        to_response(verify_possession_inner(&raw_public_key, &raw_proof))
+    })
+}
+
+/// Converts the outcome of a BLS operation into a response. All [`BLSError`]s are caused by
+/// invalid arguments.
+pub(super) fn to_response<T: Default>(
+    result: Result<T, BLSError>,
+) -> crate::util::types::Result<T> {
+    match result {
+        Ok(value) => crate::util::types::Result::ok(value),
+        Err(err) => crate::util::types::Result::err_with_code(
+            FCPResponseStatus::CallerError,
+            err as i32,
+            err.to_string().into_bytes().into_boxed_slice(),
+        ),
+    }
+}
+
+/// Decodes a compressed G2 point, telling points off the curve apart from points outside of
+/// the prime order subgroup.
+pub(super) fn decode_g2(raw: &[u8], encoding_error: BLSError) -> Result<G2Affine, BLSError> {
+    let raw: &[u8; SIGNATURE_BYTES] = raw.try_into().map_err(|_| encoding_error)?;
+    let point: G2Affine =
+        Option::from(G2Affine::from_compressed_unchecked(raw)).ok_or(encoding_error)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(BLSError::PointNotInSubgroup);
+    }
+
+    Ok(point)
+}
+
+/// Decodes a compressed G1 point, telling points off the curve apart from points outside of
+/// the prime order subgroup.
+pub(super) fn decode_g1(raw: &[u8], encoding_error: BLSError) -> Result<G1Affine, BLSError> {
+    let raw: &[u8; PUBLIC_KEY_BYTES] = raw.try_into().map_err(|_| encoding_error)?;
+    let point: G1Affine =
+        Option::from(G1Affine::from_compressed_unchecked(raw)).ok_or(encoding_error)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(BLSError::PointNotInSubgroup);
+    }
+
+    Ok(point)
+}
+
+pub(super) fn decode_signature(raw: &[u8]) -> Result<Signature, BLSError> {
+    decode_g2(raw, BLSError::InvalidSignatureEncoding).map(Into::into)
+}
+
+fn decode_digest(raw: &[u8]) -> Result<G2Projective, BLSError> {
+    decode_g2(raw, BLSError::InvalidDigestEncoding).map(Into::into)
+}
+
+pub(super) fn decode_public_key(raw: &[u8]) -> Result<PublicKey, BLSError> {
+    decode_g1(raw, BLSError::InvalidPublicKeyEncoding).map(|p| G1Projective::from(p).into())
+}
+
+pub(super) fn decode_private_key(raw: &[u8]) -> Result<PrivateKey, BLSError> {
+    PrivateKey::from_bytes(raw).map_err(|_| BLSError::InvalidPrivateKey)
+}
+
+/// Check that a public key is a compressed point of the G1 subgroup other than the identity
+///
+/// # Arguments
+///
+/// * `raw_public_key` - public key byte array (PUBLIC_KEY_BYTES long)
+#[ffi_export]
+pub fn public_key_validate(
+    raw_public_key: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSValidateResponse> {
+    catch_panic_response_raw("public_key_validate", || {
+        to_response(
+            decode_g1(&raw_public_key, BLSError::InvalidPublicKeyEncoding)
+                .and_then(|point| reject_identity(point.is_identity().into())),
+        )
+    })
+}
+
+/// Check that a signature is a compressed point of the G2 subgroup other than the identity
+///
+/// Note that the Filecoin placeholder signature returned by `create_zero_signature` is the
+/// identity and does not pass this check.
+///
+/// # Arguments
+///
+/// * `raw_signature` - signature byte array (SIGNATURE_BYTES long)
+#[ffi_export]
+pub fn signature_validate(raw_signature: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSValidateResponse> {
+    catch_panic_response_raw("signature_validate", || {
+        to_response(
+            decode_g2(&raw_signature, BLSError::InvalidSignatureEncoding)
+                .and_then(|point| reject_identity(point.is_identity().into())),
+        )
+    })
+}
+
+/// Convert a compressed G1 point, e.g. a public key, to its uncompressed form
+/// (G1_UNCOMPRESSED_BYTES long)
+///
+/// Fails with `InvalidPublicKeyEncoding` or `PointNotInSubgroup`.
+#[ffi_export]
+pub fn g1_decompress(raw_point: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSPointResponse> {
+    catch_panic_response_raw("g1_decompress", || {
+        to_response(
+            decode_g1(&raw_point, BLSError::InvalidPublicKeyEncoding)
+                .map(|point| point.to_uncompressed().to_vec().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Convert an uncompressed G1 point to its compressed form (PUBLIC_KEY_BYTES long)
+///
+/// Fails with `InvalidPublicKeyEncoding` or `PointNotInSubgroup`.
+#[ffi_export]
+pub fn g1_compress(raw_point: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSPointResponse> {
+    catch_panic_response_raw("g1_compress", || {
+        to_response(
+            decode_g1_uncompressed(&raw_point)
+                .map(|point| point.to_compressed().to_vec().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Convert a compressed G2 point, e.g. a signature, to its uncompressed form
+/// (G2_UNCOMPRESSED_BYTES long)
+///
+/// Fails with `InvalidSignatureEncoding` or `PointNotInSubgroup`.
+#[ffi_export]
+pub fn g2_decompress(raw_point: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSPointResponse> {
+    catch_panic_response_raw("g2_decompress", || {
+        to_response(
+            decode_g2(&raw_point, BLSError::InvalidSignatureEncoding)
+                .map(|point| point.to_uncompressed().to_vec().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Convert an uncompressed G2 point to its compressed form (SIGNATURE_BYTES long)
+///
+/// Fails with `InvalidSignatureEncoding` or `PointNotInSubgroup`.
+#[ffi_export]
+pub fn g2_compress(raw_point: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSPointResponse> {
+    catch_panic_response_raw("g2_compress", || {
+        to_response(
+            decode_g2_uncompressed(&raw_point)
+                .map(|point| point.to_compressed().to_vec().into_boxed_slice().into()),
+        )
+    })
+}
+
+fn reject_identity(is_identity: bool) -> Result<(), BLSError> {
+    if is_identity {
+        return Err(BLSError::IdentityPoint);
+    }
+
+    Ok(())
+}
+
+fn decode_g1_uncompressed(raw: &[u8]) -> Result<G1Affine, BLSError> {
+    let encoding_error = BLSError::InvalidPublicKeyEncoding;
+    let raw: &[u8; G1_UNCOMPRESSED_BYTES] = raw.try_into().map_err(|_| encoding_error)?;
+    let point: G1Affine =
+        Option::from(G1Affine::from_uncompressed_unchecked(raw)).ok_or(encoding_error)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(BLSError::PointNotInSubgroup);
+    }
+
+    Ok(point)
+}
+
+fn decode_g2_uncompressed(raw: &[u8]) -> Result<G2Affine, BLSError> {
+    let encoding_error = BLSError::InvalidSignatureEncoding;
+    let raw: &[u8; G2_UNCOMPRESSED_BYTES] = raw.try_into().map_err(|_| encoding_error)?;
+    let point: G2Affine =
+        Option::from(G2Affine::from_uncompressed_unchecked(raw)).ok_or(encoding_error)?;
+    if !bool::from(point.is_torsion_free()) {
+        return Err(BLSError::PointNotInSubgroup);
+    }
+
+    Ok(point)
+}
+
+destructor!(destroy_bls_verify_response, BLSVerifyResponse);
+destructor!(destroy_bls_signature_response, BLSSignatureResponse);
+destructor!(destroy_bls_public_key_response, BLSPublicKeyResponse);
+destructor!(destroy_bls_validate_response, BLSValidateResponse);
+destructor!(destroy_bls_point_response, BLSPointResponse);
+
 /// Returns a zero signature, used as placeholder in Filecoin.
 ///
 /// The return value is a pointer to a compressed signature in bytes, of length `SIGNATURE_BYTES`
@@ -282,6 +1124,14 @@ pub fn create_zero_signature() -> repr_c::Box<BLSSignature> {
 mod tests {
     use super::*;
 
+    /// On the curve, but not in the G1 subgroup.
+    const NOT_IN_G1: [u8; PUBLIC_KEY_BYTES] = [
+        0x81, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd,
+        0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab,
+        0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x89,
+        0xab, 0xcd, 0xef,
+    ];
+
     #[test]
     fn key_verification() {
         let private_key = private_key_generate();
@@ -330,6 +1180,347 @@ mod tests {
         assert!(!not_verified);
     }
 
+    #[test]
+    fn batch_verification() {
+        let private_keys: Vec<_> = (0..8).map(|_| private_key_generate()).collect();
+        let messages: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; 10 + i as usize]).collect();
+
+        let mut flattened_signatures = Vec::new();
+        let mut flattened_public_keys = Vec::new();
+        for (private_key, message) in private_keys.iter().zip(&messages) {
+            let signature = private_key_sign(private_key[..].into(), message[..].into()).unwrap();
+            let public_key = private_key_public_key(private_key[..].into()).unwrap();
+            flattened_signatures.extend_from_slice(&signature[..]);
+            flattened_public_keys.extend_from_slice(&public_key[..]);
+        }
+        let flattened_messages = messages.concat();
+        let message_sizes: Vec<usize> = messages.iter().map(Vec::len).collect();
+
+        let result = batch_verify(
+            flattened_signatures[..].into(),
+            flattened_messages[..].into(),
+            message_sizes[..].into(),
+            flattened_public_keys[..].into(),
+        );
+        assert!(result.valid);
+
+        // replace two signatures with ones over a different message
+        let mut tampered = flattened_signatures.clone();
+        for index in [5, 3] {
+            let signature =
+                private_key_sign(private_keys[index][..].into(), b"tampered"[..].into()).unwrap();
+            tampered[index * SIGNATURE_BYTES..(index + 1) * SIGNATURE_BYTES]
+                .copy_from_slice(&signature[..]);
+        }
+        let result = batch_verify(
+            tampered[..].into(),
+            flattened_messages[..].into(),
+            message_sizes[..].into(),
+            flattened_public_keys[..].into(),
+        );
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_index, 3);
+
+        // a missing public key fails the last entry
+        let result = batch_verify(
+            flattened_signatures[..].into(),
+            flattened_messages[..].into(),
+            message_sizes[..].into(),
+            flattened_public_keys[..7 * PUBLIC_KEY_BYTES].into(),
+        );
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_index, 7);
+
+        // a message size overrunning the messages fails its entry
+        let mut overrun = message_sizes.clone();
+        overrun[4] = flattened_messages.len();
+        let result = batch_verify(
+            flattened_signatures[..].into(),
+            flattened_messages[..].into(),
+            overrun[..].into(),
+            flattened_public_keys[..].into(),
+        );
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_index, 4);
+
+        // garbage signature bytes
+        let mut garbage = flattened_signatures.clone();
+        garbage[6 * SIGNATURE_BYTES..7 * SIGNATURE_BYTES].fill(0xff);
+        let result = batch_verify(
+            garbage[..].into(),
+            flattened_messages[..].into(),
+            message_sizes[..].into(),
+            flattened_public_keys[..].into(),
+        );
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_index, 6);
+
+        let empty: [usize; 0] = [];
+        let result = batch_verify(
+            [][..].into(),
+            [][..].into(),
+            empty[..].into(),
+            [][..].into(),
+        );
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_index, 0);
+    }
+
+    #[test]
+    fn proof_of_possession() {
+        let private_key = private_key_generate();
+        let public_key = private_key_public_key(private_key[..].into()).unwrap();
+        let proof = private_key_prove_possession(private_key[..].into()).unwrap();
+
+        assert!(public_key_verify_possession(
+            public_key[..].into(),
+            proof[..].into()
+        ));
+
+        // a proof for another key does not verify
+        let other_private_key = private_key_generate();
+        let other_proof = private_key_prove_possession(other_private_key[..].into()).unwrap();
+        assert!(!public_key_verify_possession(
+            public_key[..].into(),
+            other_proof[..].into()
+        ));
+
+        // a regular signature over the public key is not a proof of possession
+        let signature = private_key_sign(private_key[..].into(), public_key[..].into()).unwrap();
+        assert!(!public_key_verify_possession(
+            public_key[..].into(),
+            signature[..].into()
+        ));
+
+        // garbage
+        assert!(!public_key_verify_possession(
+            public_key[..].into(),
+            [0, 1, 2, 3][..].into()
+        ));
+    }
+
+    #[test]
+    fn fast_aggregate_verification() {
+        let message = b"common message";
+        let private_keys: Vec<_> = (0..5).map(|_| private_key_generate()).collect();
+
+        let mut flattened_signatures = Vec::new();
+        let mut flattened_public_keys = Vec::new();
+        for private_key in &private_keys {
+            let signature = private_key_sign(private_key[..].into(), message[..].into()).unwrap();
+            let public_key = private_key_public_key(private_key[..].into()).unwrap();
+            flattened_signatures.extend_from_slice(&signature[..]);
+            flattened_public_keys.extend_from_slice(&public_key[..]);
+        }
+        let signature = aggregate(flattened_signatures[..].into()).unwrap();
+
+        assert!(fast_aggregate_verify(
+            signature[..].into(),
+            message[..].into(),
+            flattened_public_keys[..].into(),
+        ));
+
+        // the aggregated public key verifies like a regular one
+        let public_key = aggregate_public_keys(flattened_public_keys[..].into()).unwrap();
+        let digest = hash(message[..].into());
+        assert!(verify(
+            signature[..].into(),
+            digest[..].into(),
+            public_key[..].into(),
+        ));
+
+        // missing signer
+        assert!(!fast_aggregate_verify(
+            signature[..].into(),
+            message[..].into(),
+            flattened_public_keys[..4 * PUBLIC_KEY_BYTES].into(),
+        ));
+        assert!(!fast_aggregate_verify(
+            signature[..].into(),
+            b"other message"[..].into(),
+            flattened_public_keys[..].into(),
+        ));
+        assert!(!fast_aggregate_verify(
+            signature[..].into(),
+            message[..].into(),
+            [][..].into(),
+        ));
+        assert!(aggregate_public_keys([][..].into()).is_none());
+        assert!(aggregate_public_keys(flattened_public_keys[1..].into()).is_none());
+    }
+
+    #[test]
+    fn custom_dst() {
+        let dst = b"MY_APP_BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        let messages: [&[u8]; 2] = [b"first message", b"second message"];
+        let private_keys: Vec<_> = (0..2).map(|_| private_key_generate()).collect();
+
+        // the Filecoin tag reproduces the existing functions
+        let digest = hash_dst(messages[0].into(), FILECOIN_DST.into()).unwrap();
+        assert_eq!(&digest[..], &hash(messages[0].into())[..]);
+        let signature = private_key_sign_dst(
+            private_keys[0][..].into(),
+            messages[0].into(),
+            FILECOIN_DST.into(),
+        )
+        .unwrap();
+        assert_eq!(
+            &signature[..],
+            &private_key_sign(private_keys[0][..].into(), messages[0].into()).unwrap()[..]
+        );
+
+        let mut signatures = Vec::new();
+        let mut flattened_public_keys = Vec::new();
+        for (private_key, message) in private_keys.iter().zip(messages) {
+            let public_key = private_key_public_key(private_key[..].into()).unwrap();
+            let signature =
+                private_key_sign_dst(private_key[..].into(), message.into(), dst[..].into())
+                    .unwrap();
+
+            assert!(verify_dst(
+                signature[..].into(),
+                message.into(),
+                public_key[..].into(),
+                dst[..].into(),
+            ));
+            assert!(!verify_dst(
+                signature[..].into(),
+                message.into(),
+                public_key[..].into(),
+                FILECOIN_DST.into(),
+            ));
+            let digest = hash_dst(message.into(), dst[..].into()).unwrap();
+            assert!(verify(
+                signature[..].into(),
+                digest[..].into(),
+                public_key[..].into(),
+            ));
+
+            signatures.extend_from_slice(&signature[..]);
+            flattened_public_keys.extend_from_slice(&public_key[..]);
+        }
+
+        let aggregated = aggregate(signatures[..].into()).unwrap();
+        let flattened_messages = messages.concat();
+        let message_sizes: Vec<usize> = messages.iter().map(|m| m.len()).collect();
+        let hash_verify_with = |dst: &[u8]| {
+            hash_verify_dst(
+                aggregated[..].into(),
+                flattened_messages[..].into(),
+                message_sizes[..].into(),
+                flattened_public_keys[..].into(),
+                dst.into(),
+            )
+        };
+        assert!(hash_verify_with(dst));
+        assert!(!hash_verify_with(FILECOIN_DST));
+        assert!(!hash_verify_with(b""));
+
+        assert!(hash_dst(messages[0].into(), b""[..].into()).is_none());
+        assert!(private_key_sign_dst(
+            private_keys[0][..].into(),
+            messages[0].into(),
+            b""[..].into()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn result_error_codes() {
+        fn assert_caller_error(status: FCPResponseStatus, error_code: i32, expected: BLSError) {
+            assert_eq!(status, FCPResponseStatus::CallerError);
+            assert_eq!(error_code, expected as i32);
+        }
+
+        let private_key = private_key_generate();
+        let public_key = private_key_public_key(private_key[..].into()).unwrap();
+        let message = b"hello world";
+        let digest = hash(message[..].into());
+        let signature = private_key_sign(private_key[..].into(), message[..].into()).unwrap();
+
+        let resp = verify_result(
+            signature[..].into(),
+            digest[..].into(),
+            public_key[..].into(),
+        );
+        assert_eq!(resp.status_code, FCPResponseStatus::NoError);
+        assert!(resp.value);
+
+        // a well formed, but wrong signature is not an error
+        let other_digest = hash(b"bye world"[..].into());
+        let resp = verify_result(
+            signature[..].into(),
+            other_digest[..].into(),
+            public_key[..].into(),
+        );
+        assert_eq!(resp.status_code, FCPResponseStatus::NoError);
+        assert!(!resp.value);
+
+        let resp = verify_result(
+            signature[1..].into(),
+            digest[..].into(),
+            public_key[..].into(),
+        );
+        assert_caller_error(
+            resp.status_code,
+            resp.error_code,
+            BLSError::InvalidSignatureEncoding,
+        );
+
+        let resp = verify_result(
+            signature[..].into(),
+            [digest[..].to_vec(), other_digest[..].to_vec()].concat()[..].into(),
+            public_key[..].into(),
+        );
+        assert_caller_error(resp.status_code, resp.error_code, BLSError::CountMismatch);
+
+        let not_in_g1 = NOT_IN_G1;
+        let resp = verify_result(
+            signature[..].into(),
+            digest[..].into(),
+            not_in_g1[..].into(),
+        );
+        assert_caller_error(
+            resp.status_code,
+            resp.error_code,
+            BLSError::PointNotInSubgroup,
+        );
+
+        // message sizes pointing past the end of the messages must not panic
+        let message_sizes = [message.len(), 100];
+        let resp = hash_verify_result(
+            signature[..].into(),
+            message[..].into(),
+            message_sizes[..].into(),
+            public_key[..].into(),
+        );
+        assert_caller_error(
+            resp.status_code,
+            resp.error_code,
+            BLSError::MalformedMessages,
+        );
+        assert!(!hash_verify(
+            signature[..].into(),
+            message[..].into(),
+            message_sizes[..].into(),
+            public_key[..].into(),
+        ));
+
+        let resp = private_key_sign_result([0xff; 32][..].into(), message[..].into());
+        assert_caller_error(
+            resp.status_code,
+            resp.error_code,
+            BLSError::InvalidPrivateKey,
+        );
+
+        let resp = private_key_sign_result(private_key[..].into(), message[..].into());
+        assert_eq!(resp.status_code, FCPResponseStatus::NoError);
+        assert_eq!(&resp.value[..], &signature[..]);
+
+        let resp = aggregate_result([][..].into());
+        assert_caller_error(resp.status_code, resp.error_code, BLSError::EmptyInput);
+    }
+
     #[test]
     fn private_key_with_seed() {
         let seed = [5u8; 32];
@@ -343,6 +1534,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn point_validation() {
+        let private_key = private_key_generate();
+        let public_key = private_key_public_key(private_key[..].into()).unwrap();
+        let signature = private_key_sign(private_key[..].into(), b"hello"[..].into()).unwrap();
+
+        let resp = public_key_validate(public_key[..].into());
+        assert_eq!(resp.status_code, FCPResponseStatus::NoError);
+        let resp = signature_validate(signature[..].into());
+        assert_eq!(resp.status_code, FCPResponseStatus::NoError);
+
+        let resp = public_key_validate(NOT_IN_G1[..].into());
+        assert_eq!(resp.error_code, BLSError::PointNotInSubgroup as i32);
+        let resp = public_key_validate(public_key[1..].into());
+        assert_eq!(resp.error_code, BLSError::InvalidPublicKeyEncoding as i32);
+        let resp = public_key_validate(G1Affine::identity().to_compressed()[..].into());
+        assert_eq!(resp.error_code, BLSError::IdentityPoint as i32);
+        let resp = signature_validate(create_zero_signature()[..].into());
+        assert_eq!(resp.status_code, FCPResponseStatus::CallerError);
+        assert_eq!(resp.error_code, BLSError::IdentityPoint as i32);
+        let mut malformed = *signature;
+        malformed[0] &= 0x7f; // clear the compression flag
+        let resp = signature_validate(malformed[..].into());
+        assert_eq!(resp.error_code, BLSError::InvalidSignatureEncoding as i32);
+
+        // compression round trips
+        let uncompressed = g1_decompress(public_key[..].into());
+        assert_eq!(uncompressed.value.len(), G1_UNCOMPRESSED_BYTES);
+        let compressed = g1_compress(uncompressed.value.as_ref());
+        assert_eq!(&compressed.value[..], &public_key[..]);
+
+        let uncompressed = g2_decompress(signature[..].into());
+        assert_eq!(uncompressed.value.len(), G2_UNCOMPRESSED_BYTES);
+        let compressed = g2_compress(uncompressed.value.as_ref());
+        assert_eq!(&compressed.value[..], &signature[..]);
+
+        let resp = g1_decompress(NOT_IN_G1[..].into());
+        assert_eq!(resp.error_code, BLSError::PointNotInSubgroup as i32);
+        let resp = g1_compress(public_key[..].into());
+        assert_eq!(resp.error_code, BLSError::InvalidPublicKeyEncoding as i32);
+        let mut off_curve = g2_decompress(signature[..].into()).value.to_vec();
+        off_curve[G2_UNCOMPRESSED_BYTES - 1] ^= 1;
+        let resp = g2_compress(off_curve[..].into());
+        assert_eq!(resp.error_code, BLSError::InvalidSignatureEncoding as i32);
+    }
+
     #[test]
     fn test_zero_key() {
         let resp = create_zero_signature();
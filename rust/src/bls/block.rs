@@ -0,0 +1,433 @@
+use std::collections::HashSet;
+
+use bls_signatures::verify_messages;
+use cid::multihash::Multihash;
+use cid::Cid;
+use rayon::prelude::*;
+use safer_ffi::prelude::*;
+
+use super::api::{decode_signature, split_messages, PUBLIC_KEY_BYTES};
+use super::cache::decode_public_keys;
+use super::types::BLSError;
+
+/// Multicodec of DAG-CBOR.
+const DAG_CBOR: u64 = 0x71;
+/// Multihash code of BLAKE2b-256.
+const BLAKE2B_256: u64 = 0xb220;
+/// Number of fields of an unsigned message.
+const MESSAGE_FIELDS: u64 = 10;
+/// Position of the sender address among the message fields.
+const MESSAGE_FROM_FIELD: usize = 2;
+/// Address protocol of BLS public keys.
+const BLS_ADDRESS_PROTOCOL: u8 = 3;
+/// Nesting limit of CBOR items, messages carry their parameters as bytes and are never deep.
+const MAX_CBOR_DEPTH: usize = 16;
+
+/// Outcome of a [`block_verify_bls_aggregate`] call.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BLSBlockVerify {
+    /// `true` if the aggregate signature is valid for all messages of the block.
+    pub valid: bool,
+    /// `BLSError` code of the failure, `0` if the aggregate signature is well formed but doesn't
+    /// verify. Only meaningful if `valid` is `false`.
+    pub error_code: i32,
+    /// Index of the message that made the block invalid. Failures of the aggregate signature
+    /// can't be attributed to a single message and are reported at the number of messages.
+    /// Only meaningful if `valid` is `false`.
+    pub invalid_index: u64,
+}
+
+/// Verify the BLS aggregate signature of a block against its BLS messages
+///
+/// Each message is signed over the bytes of its CID (CIDv1, DAG-CBOR, BLAKE2b-256), which are
+/// computed here. Messages must be distinct, and senders given as BLS addresses must match their
+/// public key. Senders given as ID addresses can't be resolved here, so the caller is
+/// responsible for looking up their keys.
+///
+/// # Arguments
+///
+/// * `aggregate_signature`   - the `BLSAggregate` of the block (SIGNATURE_BYTES long)
+/// * `flattened_messages`    - byte array containing the CBOR encoded unsigned messages
+/// * `message_sizes`         - array containing the lengths of the messages
+/// * `flattened_public_keys` - byte array containing the public keys of the senders
+#[ffi_export]
+pub fn block_verify_bls_aggregate(
+    aggregate_signature: c_slice::Ref<'_, u8>,
+    flattened_messages: c_slice::Ref<'_, u8>,
+    message_sizes: c_slice::Ref<'_, libc::size_t>,
+    flattened_public_keys: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSBlockVerify> {
+    let result = verify_block(
+        &aggregate_signature,
+        &flattened_messages,
+        &message_sizes,
+        &flattened_public_keys,
+    );
+
+    let outcome = match result {
+        Ok(true) => BLSBlockVerify {
+            valid: true,
+            ..Default::default()
+        },
+        Ok(false) => BLSBlockVerify {
+            valid: false,
+            error_code: 0,
+            invalid_index: message_sizes.len() as u64,
+        },
+        Err((error, index)) => BLSBlockVerify {
+            valid: false,
+            error_code: error as i32,
+            invalid_index: index as u64,
+        },
+    };
+
+    Box::new(outcome).into()
+}
+
+#[ffi_export]
+fn destroy_box_bls_block_verify(ptr: repr_c::Box<BLSBlockVerify>) {
+    drop(ptr);
+}
+
+/// Returns whether the aggregate verifies, or the error and the index of the message it
+/// belongs to.
+fn verify_block(
+    aggregate_signature: &[u8],
+    flattened_messages: &[u8],
+    message_sizes: &[libc::size_t],
+    flattened_public_keys: &[u8],
+) -> Result<bool, (BLSError, usize)> {
+    let messages = split_messages(flattened_messages, message_sizes)?;
+
+    let raw_public_keys: Vec<&[u8]> = flattened_public_keys.chunks(PUBLIC_KEY_BYTES).collect();
+    if raw_public_keys.len() != messages.len() {
+        let index = raw_public_keys.len().min(messages.len());
+        return Err((BLSError::CountMismatch, index));
+    }
+    if messages.is_empty() {
+        return Err((BLSError::EmptyInput, 0));
+    }
+
+    let public_keys = decode_public_keys(flattened_public_keys)?;
+
+    let cids: Vec<Vec<u8>> = messages
+        .par_iter()
+        .zip(raw_public_keys.par_iter())
+        .enumerate()
+        .map(|(i, (message, raw_public_key))| {
+            check_message(message, raw_public_key).map_err(|error| (error, i))?;
+            Ok(message_cid(message))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut seen = HashSet::with_capacity(cids.len());
+    if let Some(index) = cids.iter().position(|cid| !seen.insert(cid)) {
+        return Err((BLSError::DuplicateMessage, index));
+    }
+
+    let signature =
+        decode_signature(aggregate_signature).map_err(|error| (error, messages.len()))?;
+    let cids: Vec<&[u8]> = cids.iter().map(Vec::as_slice).collect();
+
+    Ok(verify_messages(&signature, &cids, &public_keys))
+}
+
+/// The signing bytes of a message, the bytes of its CID.
+fn message_cid(message: &[u8]) -> Vec<u8> {
+    let digest = blake2b_simd::Params::new().hash_length(32).hash(message);
+    let multihash = Multihash::wrap(BLAKE2B_256, digest.as_bytes()).expect("digest fits");
+
+    Cid::new_v1(DAG_CBOR, multihash).to_bytes()
+}
+
+/// Checks that a message is a CBOR array of the message fields, and that a BLS sender address
+/// matches the public key.
+fn check_message(message: &[u8], raw_public_key: &[u8]) -> Result<(), BLSError> {
+    let (major, fields, mut offset) =
+        cbor_header(message).ok_or(BLSError::InvalidMessageEncoding)?;
+    if major != 4 || fields != MESSAGE_FIELDS {
+        return Err(BLSError::InvalidMessageEncoding);
+    }
+
+    let mut from = None;
+    for field in 0..MESSAGE_FIELDS as usize {
+        let len = cbor_item_len(&message[offset..], 0).ok_or(BLSError::InvalidMessageEncoding)?;
+        if field == MESSAGE_FROM_FIELD {
+            from = Some(&message[offset..offset + len]);
+        }
+        offset += len;
+    }
+    if offset != message.len() {
+        return Err(BLSError::InvalidMessageEncoding);
+    }
+
+    let from = from.expect("message has a sender");
+    let (major, len, header_len) = cbor_header(from).ok_or(BLSError::InvalidMessageEncoding)?;
+    if major != 2 || len == 0 {
+        return Err(BLSError::InvalidMessageEncoding);
+    }
+    let address = &from[header_len..];
+    if address[0] == BLS_ADDRESS_PROTOCOL && &address[1..] != raw_public_key {
+        return Err(BLSError::SenderMismatch);
+    }
+
+    Ok(())
+}
+
+/// Parses the header of the CBOR item at the start of `data` into its major type, argument and
+/// length. Indefinite lengths are not allowed in DAG-CBOR and are rejected.
+fn cbor_header(data: &[u8]) -> Option<(u8, u64, usize)> {
+    let first = *data.first()?;
+    let major = first >> 5;
+    let (argument, len) = match first & 0x1f {
+        info @ 0..=23 => (u64::from(info), 1),
+        info @ 24..=27 => {
+            let bytes = 1usize << (info - 24);
+            let raw = data.get(1..1 + bytes)?;
+            let argument = raw.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+            (argument, 1 + bytes)
+        }
+        _ => return None,
+    };
+
+    Some((major, argument, len))
+}
+
+/// Returns the length of the CBOR item at the start of `data`.
+fn cbor_item_len(data: &[u8], depth: usize) -> Option<usize> {
+    if depth > MAX_CBOR_DEPTH {
+        return None;
+    }
+    let (major, argument, mut len) = cbor_header(data)?;
+    match major {
+        // integers, simple values and floats
+        0 | 1 | 7 => {}
+        // byte and text strings
+        2 | 3 => {
+            len = len.checked_add(usize::try_from(argument).ok()?)?;
+        }
+        // arrays and maps
+        4 | 5 => {
+            let items = if major == 5 {
+                argument.checked_mul(2)?
+            } else {
+                argument
+            };
+            for _ in 0..items {
+                len += cbor_item_len(data.get(len..)?, depth + 1)?;
+            }
+        }
+        // tags
+        6 => {
+            len += cbor_item_len(data.get(len..)?, depth + 1)?;
+        }
+        _ => unreachable!("three bit major type"),
+    }
+
+    (len <= data.len()).then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::{aggregate, private_key_generate, private_key_public_key};
+    use super::*;
+    use bls_signatures::{PrivateKey, Serialize};
+
+    /// A CBOR encoded message from `from`, made unique by `nonce`.
+    fn message(from: &[u8], nonce: u8) -> Vec<u8> {
+        let mut message = vec![0x8a, 0x00, 0x42, 0x00, 0x01];
+        message.extend_from_slice(&[0x58, from.len() as u8]);
+        message.extend_from_slice(from);
+        message.push(nonce);
+        message.extend_from_slice(&[0x40, 0x19, 0x03, 0xe8, 0x40, 0x40, 0x00, 0x40]);
+        message
+    }
+
+    fn bls_address(raw_public_key: &[u8]) -> Vec<u8> {
+        std::iter::once(BLS_ADDRESS_PROTOCOL)
+            .chain(raw_public_key.iter().copied())
+            .collect()
+    }
+
+    struct Block {
+        messages: Vec<Vec<u8>>,
+        public_keys: Vec<u8>,
+        signature: Vec<u8>,
+    }
+
+    impl Block {
+        fn new(count: u8) -> Self {
+            let mut block = Block {
+                messages: Vec::new(),
+                public_keys: Vec::new(),
+                signature: Vec::new(),
+            };
+            let mut signatures = Vec::new();
+            for nonce in 0..count {
+                let private_key = private_key_generate();
+                let public_key = private_key_public_key(private_key[..].into()).unwrap();
+                // alternate between BLS and ID address senders
+                let from = match nonce % 2 {
+                    0 => bls_address(&public_key[..]),
+                    _ => vec![0x00, 0x64],
+                };
+                let message = message(&from, nonce);
+                let private_key = PrivateKey::from_bytes(&private_key[..]).unwrap();
+                signatures.extend(private_key.sign(message_cid(&message)).as_bytes());
+                block.messages.push(message);
+                block.public_keys.extend_from_slice(&public_key[..]);
+            }
+            if let Some(signature) = aggregate(signatures[..].into()) {
+                block.signature = signature.to_vec();
+            }
+            block
+        }
+
+        fn verify(&self) -> BLSBlockVerify {
+            let flattened: Vec<u8> = self.messages.iter().flatten().copied().collect();
+            let sizes: Vec<usize> = self.messages.iter().map(Vec::len).collect();
+            *block_verify_bls_aggregate(
+                self.signature[..].into(),
+                flattened[..].into(),
+                sizes[..].into(),
+                self.public_keys[..].into(),
+            )
+        }
+    }
+
+    fn invalid(error_code: i32, invalid_index: u64) -> BLSBlockVerify {
+        BLSBlockVerify {
+            valid: false,
+            error_code,
+            invalid_index,
+        }
+    }
+
+    #[test]
+    fn verify_block_aggregate() {
+        let block = Block::new(4);
+        assert!(block.verify().valid);
+
+        let mut reordered = Block::new(4);
+        reordered.messages.swap(1, 2);
+        assert_eq!(
+            reordered.verify(),
+            invalid(BLSError::SenderMismatch as i32, 1)
+        );
+
+        let mut tampered = Block::new(4);
+        *tampered.messages[3].last_mut().unwrap() = 0x01;
+        assert_eq!(tampered.verify(), invalid(0, 4));
+
+        let mut duplicated = Block::new(3);
+        duplicated.messages[2] = duplicated.messages[0].clone();
+        duplicated
+            .public_keys
+            .copy_within(0..PUBLIC_KEY_BYTES, 2 * PUBLIC_KEY_BYTES);
+        assert_eq!(
+            duplicated.verify(),
+            invalid(BLSError::DuplicateMessage as i32, 2)
+        );
+
+        let mut malformed = Block::new(3);
+        malformed.messages[1].push(0x00);
+        assert_eq!(
+            malformed.verify(),
+            invalid(BLSError::InvalidMessageEncoding as i32, 1)
+        );
+
+        let mut bad_key = Block::new(3);
+        bad_key.public_keys[PUBLIC_KEY_BYTES * 2] &= 0x7f;
+        assert_eq!(
+            bad_key.verify(),
+            invalid(BLSError::InvalidPublicKeyEncoding as i32, 2)
+        );
+
+        let mut missing_key = Block::new(3);
+        missing_key.public_keys.truncate(PUBLIC_KEY_BYTES);
+        assert_eq!(
+            missing_key.verify(),
+            invalid(BLSError::CountMismatch as i32, 1)
+        );
+
+        let mut bad_signature = Block::new(2);
+        bad_signature.signature.pop();
+        assert_eq!(
+            bad_signature.verify(),
+            invalid(BLSError::InvalidSignatureEncoding as i32, 2)
+        );
+
+        let empty = Block::new(0);
+        assert_eq!(empty.verify(), invalid(BLSError::EmptyInput as i32, 0));
+
+        let overrun = Block::new(2);
+        let flattened: Vec<u8> = overrun.messages.iter().flatten().copied().collect();
+        let sizes = [overrun.messages[0].len(), overrun.messages[1].len() + 1];
+        assert_eq!(
+            verify_block(&overrun.signature, &flattened, &sizes, &overrun.public_keys),
+            Err((BLSError::MalformedMessages, 1))
+        );
+    }
+
+    #[test]
+    fn cbor_validation() {
+        for item in [
+            &[0x00][..],
+            &[0x18, 0x64],
+            &[0x43, 1, 2, 3],
+            &[0x82, 0x01, 0x40],
+            &[0xa1, 0x61, b'a', 0xf6],
+            &[0xd8, 0x2a, 0x41, 0x00],
+        ] {
+            assert_eq!(cbor_item_len(item, 0), Some(item.len()), "{item:?}");
+        }
+        for item in [
+            &[][..],
+            &[0x18],
+            &[0x43, 1, 2],
+            &[0x82, 0x01],
+            &[0x9f, 0x01, 0xff],
+            &[0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        ] {
+            assert_eq!(cbor_item_len(item, 0), None, "{item:?}");
+        }
+
+        let nested = [vec![0x81; MAX_CBOR_DEPTH + 2], vec![0x00]].concat();
+        assert_eq!(cbor_item_len(&nested, 0), None);
+    }
+
+    #[cfg(feature = "fvm")]
+    #[test]
+    fn matches_fvm_message_encoding() {
+        use fvm4_shared::address::Address;
+        use fvm4_shared::econ::TokenAmount;
+        use fvm4_shared::message::Message;
+
+        let public_key = private_key_public_key(private_key_generate()[..].into()).unwrap();
+        let message = Message {
+            version: 0,
+            from: Address::new_bls(&public_key[..]).unwrap(),
+            to: Address::new_id(1000),
+            sequence: 7,
+            value: TokenAmount::from_atto(12345),
+            method_num: 2,
+            params: vec![1, 2, 3].into(),
+            gas_limit: 1_000_000,
+            gas_fee_cap: TokenAmount::from_atto(100),
+            gas_premium: TokenAmount::from_atto(10),
+        };
+        let encoded = fvm_ipld_encoding::to_vec(&message).unwrap();
+
+        assert_eq!(check_message(&encoded, &public_key[..]), Ok(()));
+        assert_eq!(
+            check_message(&encoded, &[0u8; PUBLIC_KEY_BYTES]),
+            Err(BLSError::SenderMismatch)
+        );
+
+        // Filecoin message CIDs are BLAKE2b-256 DAG-CBOR CIDs
+        let cid = Cid::try_from(message_cid(&encoded)).unwrap();
+        assert!(cid.to_string().starts_with("bafy2bzace"));
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use bls_signatures::PublicKey;
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use safer_ffi::prelude::*;
+
+use super::api::{decode_public_key, PUBLIC_KEY_BYTES};
+use super::types::BLSError;
+
+lazy_static! {
+    static ref PUBLIC_KEY_CACHE: Mutex<PublicKeyCache> = Mutex::new(PublicKeyCache::default());
+}
+
+/// Usage statistics of the public key cache.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BLSPublicKeyCacheStats {
+    /// Maximum number of cached keys, `0` if the cache is disabled.
+    pub capacity: u64,
+    /// Number of cached keys.
+    pub size: u64,
+    /// Number of keys found in the cache since it was last enabled or cleared.
+    pub hits: u64,
+    /// Number of keys that had to be decoded since the cache was last enabled or cleared.
+    pub misses: u64,
+}
+
+/// Decoded public keys by their compressed bytes. When full, the least recently used key is
+/// evicted.
+#[derive(Default)]
+struct PublicKeyCache {
+    capacity: usize,
+    /// The keys and when they were last used.
+    keys: HashMap<[u8; PUBLIC_KEY_BYTES], (PublicKey, u64)>,
+    /// The cached keys by when they were last used.
+    order: BTreeMap<u64, [u8; PUBLIC_KEY_BYTES]>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl PublicKeyCache {
+    /// Clears the cache and changes its capacity.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.clear();
+        self.capacity = capacity;
+    }
+
+    fn clear(&mut self) {
+        self.keys.clear();
+        self.order.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Looks up a key, marking it as the most recently used one.
+    fn get(&mut self, raw: &[u8; PUBLIC_KEY_BYTES]) -> Option<PublicKey> {
+        let (public_key, used) = self.keys.get_mut(raw)?;
+        self.clock += 1;
+        self.order.remove(used);
+        *used = self.clock;
+        self.order.insert(self.clock, *raw);
+
+        Some(*public_key)
+    }
+
+    fn insert(&mut self, raw: [u8; PUBLIC_KEY_BYTES], public_key: PublicKey) {
+        if self.capacity == 0 || self.keys.contains_key(&raw) {
+            return;
+        }
+        while self.keys.len() >= self.capacity {
+            match self.order.pop_first() {
+                Some((_, least_recent)) => self.keys.remove(&least_recent),
+                None => break,
+            };
+        }
+        self.clock += 1;
+        self.keys.insert(raw, (public_key, self.clock));
+        self.order.insert(self.clock, raw);
+    }
+
+    fn stats(&self) -> BLSPublicKeyCacheStats {
+        BLSPublicKeyCacheStats {
+            capacity: self.capacity as u64,
+            size: self.keys.len() as u64,
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+/// Enable the decoded public key cache, keeping at most `capacity` keys
+///
+/// Verification results are the same with and without the cache, only repeated keys get
+/// faster. Changing the capacity clears the cache, a capacity of `0` disables it.
+#[ffi_export]
+pub fn bls_public_key_cache_enable(capacity: u64) {
+    PUBLIC_KEY_CACHE
+        .lock()
+        .expect("poisoned")
+        .set_capacity(capacity as usize);
+}
+
+/// Remove all keys from the public key cache and reset its statistics
+#[ffi_export]
+pub fn bls_public_key_cache_clear() {
+    PUBLIC_KEY_CACHE.lock().expect("poisoned").clear();
+}
+
+/// Return the usage statistics of the public key cache
+#[ffi_export]
+pub fn bls_public_key_cache_stats() -> repr_c::Box<BLSPublicKeyCacheStats> {
+    Box::new(PUBLIC_KEY_CACHE.lock().expect("poisoned").stats()).into()
+}
+
+#[ffi_export]
+fn destroy_box_bls_public_key_cache_stats(ptr: repr_c::Box<BLSPublicKeyCacheStats>) {
+    drop(ptr);
+}
+
+/// Decodes the flattened public keys, looking them up in the cache if it is enabled. Keys that
+/// are not cached are decoded in parallel and added to it if they are valid. Fails with the error
+/// of the first key that doesn't decode and its index.
+pub(super) fn decode_public_keys(
+    flattened_public_keys: &[u8],
+) -> Result<Vec<PublicKey>, (BLSError, usize)> {
+    decode_public_keys_with(&PUBLIC_KEY_CACHE, flattened_public_keys)
+}
+
+fn decode_public_keys_with(
+    cache: &Mutex<PublicKeyCache>,
+    flattened_public_keys: &[u8],
+) -> Result<Vec<PublicKey>, (BLSError, usize)> {
+    let chunks = flattened_public_keys.chunks(PUBLIC_KEY_BYTES);
+
+    let cached: Vec<Option<PublicKey>> = {
+        let mut cache = cache.lock().expect("poisoned");
+        if cache.capacity == 0 {
+            drop(cache);
+            return first_error(
+                flattened_public_keys
+                    .par_chunks(PUBLIC_KEY_BYTES)
+                    .map(decode_public_key)
+                    .collect(),
+            );
+        }
+
+        let cached: Vec<_> = chunks
+            .map(|raw| {
+                let raw: &[u8; PUBLIC_KEY_BYTES] = raw.try_into().ok()?;
+                cache.get(raw)
+            })
+            .collect();
+        let hits = cached.iter().filter(|key| key.is_some()).count() as u64;
+        cache.hits += hits;
+        cache.misses += cached.len() as u64 - hits;
+        cached
+    };
+
+    let public_keys: Vec<Result<PublicKey, BLSError>> = flattened_public_keys
+        .par_chunks(PUBLIC_KEY_BYTES)
+        .zip(cached.par_iter())
+        .map(|(raw, cached)| match cached {
+            Some(public_key) => Ok(*public_key),
+            None => decode_public_key(raw),
+        })
+        .collect();
+
+    let mut cache = cache.lock().expect("poisoned");
+    for ((raw, public_key), cached) in flattened_public_keys
+        .chunks(PUBLIC_KEY_BYTES)
+        .zip(&public_keys)
+        .zip(&cached)
+    {
+        if let (Ok(public_key), None) = (public_key, cached) {
+            cache.insert(raw.try_into().expect("decoded"), *public_key);
+        }
+    }
+
+    first_error(public_keys)
+}
+
+/// Returns the decoded keys, or the error of the first key that failed to decode and its index.
+fn first_error(
+    public_keys: Vec<Result<PublicKey, BLSError>>,
+) -> Result<Vec<PublicKey>, (BLSError, usize)> {
+    public_keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, public_key)| public_key.map_err(|error| (error, i)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::{private_key_generate, private_key_public_key};
+    use super::*;
+
+    fn stats(cache: &Mutex<PublicKeyCache>) -> (u64, u64, u64) {
+        let stats = cache.lock().unwrap().stats();
+        (stats.size, stats.hits, stats.misses)
+    }
+
+    #[test]
+    fn public_key_cache() {
+        let public_keys: Vec<u8> = (0..3)
+            .flat_map(|_| *private_key_public_key(private_key_generate()[..].into()).unwrap())
+            .collect();
+        let uncached = decode_public_keys_with(&Mutex::default(), &public_keys).unwrap();
+
+        let cache = Mutex::new(PublicKeyCache::default());
+        cache.lock().unwrap().set_capacity(2);
+        assert_eq!(
+            decode_public_keys_with(&cache, &public_keys).unwrap(),
+            uncached
+        );
+        assert_eq!(stats(&cache), (2, 0, 3));
+
+        // the two most recently inserted keys are cached
+        assert_eq!(
+            decode_public_keys_with(&cache, &public_keys).unwrap(),
+            uncached
+        );
+        assert_eq!(stats(&cache), (2, 2, 4));
+
+        // using the third key again keeps it cached, the second evicts the first instead
+        let second = &public_keys[PUBLIC_KEY_BYTES..2 * PUBLIC_KEY_BYTES];
+        let third = &public_keys[2 * PUBLIC_KEY_BYTES..];
+        decode_public_keys_with(&cache, third).unwrap();
+        decode_public_keys_with(&cache, second).unwrap();
+        decode_public_keys_with(&cache, third).unwrap();
+        assert_eq!(stats(&cache), (2, 4, 5));
+
+        // invalid keys are not cached and still fail
+        let mut invalid = public_keys[..PUBLIC_KEY_BYTES].to_vec();
+        invalid[0] &= 0x7f; // clear the compression flag
+        assert!(decode_public_keys_with(&cache, &invalid).is_err());
+        assert!(decode_public_keys_with(&cache, &public_keys[1..]).is_err());
+        assert_eq!(stats(&cache).0, 2);
+
+        cache.lock().unwrap().clear();
+        assert_eq!(stats(&cache), (0, 0, 0));
+    }
+}
@@ -0,0 +1,290 @@
+//! Hierarchical deterministic key derivation as specified by
+//! [EIP-2333](https://eips.ethereum.org/EIPS/eip-2333).
+
+use bls_signatures::{PrivateKey, Serialize};
+use blstrs::Scalar;
+use group::ff::Field;
+use hkdf::Hkdf;
+use safer_ffi::prelude::*;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use super::api::to_response;
+use super::types::*;
+use crate::util::types::catch_panic_response_raw;
+
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+/// Minimum length of a seed.
+pub const SEED_MIN_BYTES: usize = 32;
+/// Number of 32 byte chunks of a Lamport secret key.
+const LAMPORT_CHUNKS: usize = 255;
+
+/// Derive the master private key of a seed
+///
+/// # Arguments
+///
+/// * `seed` - seed byte array, at least 32 bytes long
+#[ffi_export]
+pub fn bls_derive_master_key(seed: c_slice::Ref<'_, u8>) -> repr_c::Box<BLSPrivateKeyResponse> {
+    catch_panic_response_raw("bls_derive_master_key", || {
+        to_response(derive_master_key(&seed).map(private_key_bytes))
+    })
+}
+
+/// Derive the child private key at `index` of a parent private key
+///
+/// # Arguments
+///
+/// * `raw_parent_key` - parent private key byte array
+/// * `index`          - index of the child
+#[ffi_export]
+pub fn bls_derive_child_key(
+    raw_parent_key: c_slice::Ref<'_, u8>,
+    index: u32,
+) -> repr_c::Box<BLSPrivateKeyResponse> {
+    catch_panic_response_raw("bls_derive_child_key", || {
+        to_response(
+            super::api::decode_private_key(&raw_parent_key)
+                .map(|parent| private_key_bytes(derive_child_key(&parent, index))),
+        )
+    })
+}
+
+/// Derive the private key of a seed at a derivation path
+///
+/// # Arguments
+///
+/// * `seed` - seed byte array, at least 32 bytes long
+/// * `path` - UTF-8 derivation path, e.g. `m/12381/3600/0/0/0` (EIP-2334)
+#[ffi_export]
+pub fn bls_derive_path_key(
+    seed: c_slice::Ref<'_, u8>,
+    path: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSPrivateKeyResponse> {
+    catch_panic_response_raw("bls_derive_path_key", || {
+        to_response(derive_path_key(&seed, &path).map(private_key_bytes))
+    })
+}
+
+/// Same as [`bls_derive_path_key`], but returns a handle to the private key, so it never has to
+/// leave rust owned memory.
+#[ffi_export]
+pub fn bls_key_handle_from_path(
+    seed: c_slice::Ref<'_, u8>,
+    path: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSKeyHandleResponse> {
+    catch_panic_response_raw("bls_key_handle_from_path", || {
+        to_response(
+            derive_path_key(&seed, &path)
+                .map(|private_key| Some(Box::new(InnerBLSKeyHandle::new(&private_key)).into())),
+        )
+    })
+}
+
+fn private_key_bytes(private_key: PrivateKey) -> c_slice::Box<u8> {
+    private_key.as_bytes().into_boxed_slice().into()
+}
+
+fn derive_master_key(seed: &[u8]) -> Result<PrivateKey, BLSError> {
+    if seed.len() < SEED_MIN_BYTES {
+        return Err(BLSError::InvalidSeed);
+    }
+
+    Ok(hkdf_mod_r(seed))
+}
+
+fn derive_child_key(parent: &PrivateKey, index: u32) -> PrivateKey {
+    hkdf_mod_r(&parent_key_to_lamport_public_key(parent, index))
+}
+
+fn derive_path_key(seed: &[u8], path: &[u8]) -> Result<PrivateKey, BLSError> {
+    let indices = parse_path(path)?;
+
+    Ok(indices
+        .into_iter()
+        .fold(derive_master_key(seed)?, |key, index| {
+            derive_child_key(&key, index)
+        }))
+}
+
+/// Parses a path of the form `m/<index>/<index>/...` into its indices.
+fn parse_path(path: &[u8]) -> Result<Vec<u32>, BLSError> {
+    let path = std::str::from_utf8(path).map_err(|_| BLSError::InvalidDerivationPath)?;
+    let mut segments = path.split('/');
+    if segments.next() != Some("m") {
+        return Err(BLSError::InvalidDerivationPath);
+    }
+
+    segments
+        .map(|segment| {
+            // reject signs and other non canonical forms `u32::from_str` accepts
+            if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(BLSError::InvalidDerivationPath);
+            }
+            segment.parse().map_err(|_| BLSError::InvalidDerivationPath)
+        })
+        .collect()
+}
+
+/// `HKDF_mod_r` with an empty `key_info`.
+fn hkdf_mod_r(ikm: &[u8]) -> PrivateKey {
+    // L = ceil((3 * ceil(log2(r))) / 16)
+    const L: usize = 48;
+
+    let mut ikm_with_suffix = Zeroizing::new(Vec::with_capacity(ikm.len() + 1));
+    ikm_with_suffix.extend_from_slice(ikm);
+    ikm_with_suffix.push(0);
+
+    let mut salt = Sha256::digest(KEYGEN_SALT);
+    loop {
+        let mut okm = Zeroizing::new([0u8; L]);
+        Hkdf::<Sha256>::new(Some(&salt), &ikm_with_suffix)
+            .expand(&(L as u16).to_be_bytes(), &mut okm[..])
+            .expect("valid length");
+
+        // OS2IP(OKM) mod r
+        let sk = okm.iter().fold(Scalar::ZERO, |acc, byte| {
+            acc * Scalar::from(256u64) + Scalar::from(u64::from(*byte))
+        });
+        if !bool::from(sk.is_zero()) {
+            return sk.into();
+        }
+        salt = Sha256::digest(salt);
+    }
+}
+
+fn parent_key_to_lamport_public_key(parent: &PrivateKey, index: u32) -> [u8; 32] {
+    let salt = index.to_be_bytes();
+    let ikm = Zeroizing::new(Scalar::from(*parent).to_bytes_be());
+    let not_ikm = Zeroizing::new(ikm.map(|byte| !byte));
+
+    let mut lamport_public_key = Sha256::new();
+    for ikm in [&ikm, &not_ikm] {
+        let lamport_secret_key = ikm_to_lamport_secret_key(&ikm[..], &salt);
+        for chunk in lamport_secret_key.chunks(32) {
+            lamport_public_key.update(Sha256::digest(chunk));
+        }
+    }
+
+    lamport_public_key.finalize().into()
+}
+
+fn ikm_to_lamport_secret_key(ikm: &[u8], salt: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut okm = Zeroizing::new(vec![0u8; 32 * LAMPORT_CHUNKS]);
+    Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand(&[], &mut okm)
+        .expect("valid length");
+
+    okm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn from_decimal(s: &str) -> PrivateKey {
+        s.bytes()
+            .fold(Scalar::ZERO, |acc, digit| {
+                acc * Scalar::from(10u64) + Scalar::from(u64::from(digit - b'0'))
+            })
+            .into()
+    }
+
+    // Test cases from EIP-2333.
+    const TEST_CASES: [(&str, &str, u32, &str); 4] = [
+        (
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+            0,
+            "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+        ),
+        (
+            "3141592653589793238462643383279502884197169399375105820974944592",
+            "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+            3141592653,
+            "25457201688850691947727629385191704516744796114925897962676248250929345014287",
+        ),
+        (
+            "0099ff991111002299dd7744ee3355bbdd8844115566cc55663355668888cc00",
+            "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+            4294967295,
+            "29358610794459428860402234341874281240803786294062035874021252734817515685787",
+        ),
+        (
+            "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+            "19022158461524446591288038168518313374041767046816487870552872741050760015818",
+            42,
+            "31372231650479070279774297061823572166496564838472787488249775572789064611981",
+        ),
+    ];
+
+    #[test]
+    fn eip2333_test_vectors() {
+        for (seed, master, index, child) in TEST_CASES {
+            let seed = unhex(seed);
+
+            let master_key = bls_derive_master_key(seed[..].into());
+            assert_eq!(master_key.status_code, FCPResponseStatus::NoError);
+            assert_eq!(&master_key.value[..], &from_decimal(master).as_bytes()[..]);
+
+            let child_key = bls_derive_child_key(master_key.value.as_ref(), index);
+            assert_eq!(child_key.status_code, FCPResponseStatus::NoError);
+            assert_eq!(&child_key.value[..], &from_decimal(child).as_bytes()[..]);
+
+            let path = format!("m/{index}");
+            let path_key = bls_derive_path_key(seed[..].into(), path.as_bytes().into());
+            assert_eq!(&path_key.value[..], &child_key.value[..]);
+        }
+    }
+
+    #[test]
+    fn derivation_paths() {
+        let seed = [3u8; 32];
+
+        let master = derive_master_key(&seed).unwrap();
+        let expected = [12381, 3600, 0, 0, 0]
+            .into_iter()
+            .fold(master, |key, index| derive_child_key(&key, index));
+        let key = bls_derive_path_key(seed[..].into(), b"m/12381/3600/0/0/0"[..].into());
+        assert_eq!(&key.value[..], &expected.as_bytes()[..]);
+
+        let key = bls_derive_path_key(seed[..].into(), b"m"[..].into());
+        assert_eq!(&key.value[..], &master.as_bytes()[..]);
+
+        let handle = bls_key_handle_from_path(seed[..].into(), b"m/12381/3600/0/0/0"[..].into());
+        let handle = handle.value.as_ref().unwrap();
+        assert_eq!(
+            &super::super::handle::bls_key_handle_export(handle)[..],
+            &expected.as_bytes()[..]
+        );
+
+        for path in [
+            "",
+            "m/",
+            "n/0",
+            "m/-1",
+            "m/+1",
+            "m/4294967296",
+            "m/0/",
+            "m//0",
+        ] {
+            let key = bls_derive_path_key(seed[..].into(), path.as_bytes().into());
+            assert_eq!(
+                key.error_code,
+                BLSError::InvalidDerivationPath as i32,
+                "{path}"
+            );
+        }
+
+        let key = bls_derive_master_key(seed[..31].into());
+        assert_eq!(key.error_code, BLSError::InvalidSeed as i32);
+    }
+}
@@ -0,0 +1,390 @@
+use blstrs::{Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt};
+use group::prime::PrimeCurveAffine;
+use group::{Curve, Group};
+use pairing::{MillerLoopResult as _, MultiMillerLoop};
+use rayon::prelude::*;
+use safer_ffi::prelude::*;
+use sha2::{Digest, Sha256};
+
+use super::api::{decode_g1, decode_g2, BLSBatchVerify};
+use super::types::*;
+
+/// Domain separation tag of the schemes with signatures on G2.
+const G2_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+/// Domain separation tag of the schemes with signatures on G1.
+const G1_DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Verify a drand beacon entry against the group public key of its network
+///
+/// # Arguments
+///
+/// * `scheme`             - signature scheme of the network
+/// * `public_key`         - group public key (48 bytes on G1, 96 bytes on G2 for `UnchainedG1`)
+/// * `round`              - round of the entry
+/// * `previous_signature` - signature of the previous round, ignored by unchained schemes
+/// * `signature`          - signature of the entry (96 bytes on G2, 48 bytes on G1 for
+///   `UnchainedG1`)
+#[ffi_export]
+pub fn drand_verify_beacon(
+    scheme: DrandScheme,
+    public_key: c_slice::Ref<'_, u8>,
+    round: u64,
+    previous_signature: c_slice::Ref<'_, u8>,
+    signature: c_slice::Ref<'_, u8>,
+) -> bool {
+    match BeaconVerifier::new(scheme, &public_key) {
+        Some(verifier) => verifier.verify(round, &previous_signature, &signature),
+        None => false,
+    }
+}
+
+/// Verify the drand beacon entries of consecutive rounds in parallel
+///
+/// # Arguments
+///
+/// * `scheme`               - signature scheme of the network
+/// * `public_key`           - group public key, see [`drand_verify_beacon`]
+/// * `first_round`          - round of the first entry
+/// * `previous_signature`   - signature of the round before `first_round`, ignored by unchained
+///   schemes. For chained schemes every following entry is chained to the one before it.
+/// * `flattened_signatures` - signatures of the rounds `first_round, first_round + 1, ...`
+///
+/// An invalid public key, a trailing partial signature or an empty batch count as invalid.
+#[ffi_export]
+pub fn drand_verify_beacons(
+    scheme: DrandScheme,
+    public_key: c_slice::Ref<'_, u8>,
+    first_round: u64,
+    previous_signature: c_slice::Ref<'_, u8>,
+    flattened_signatures: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSBatchVerify> {
+    let signatures: Vec<&[u8]> = flattened_signatures
+        .chunks(scheme.signature_bytes())
+        .collect();
+
+    let first_invalid = match BeaconVerifier::new(scheme, &public_key) {
+        Some(_) if signatures.is_empty() => Some(0),
+        Some(verifier) => (0..signatures.len()).into_par_iter().position_first(|i| {
+            let previous = if i == 0 {
+                &previous_signature[..]
+            } else {
+                signatures[i - 1]
+            };
+            match first_round.checked_add(i as u64) {
+                Some(round) => !verifier.verify(round, previous, signatures[i]),
+                None => true,
+            }
+        }),
+        None => Some(0),
+    };
+
+    Box::new(BLSBatchVerify {
+        valid: first_invalid.is_none(),
+        first_invalid_index: first_invalid.unwrap_or_default() as u64,
+    })
+    .into()
+}
+
+impl DrandScheme {
+    fn signature_bytes(self) -> usize {
+        match self {
+            DrandScheme::Chained | DrandScheme::Unchained => 96,
+            DrandScheme::UnchainedG1 => 48,
+        }
+    }
+
+    /// The message signed for `round`.
+    fn message(self, round: u64, previous_signature: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        if self == DrandScheme::Chained {
+            hasher.update(previous_signature);
+        }
+        hasher.update(round.to_be_bytes());
+        hasher.finalize().into()
+    }
+}
+
+/// A decoded group public key, ready to verify entries of its network.
+enum BeaconVerifier {
+    G2Signatures(DrandScheme, G1Affine),
+    G1Signatures(G2Prepared),
+}
+
+impl BeaconVerifier {
+    /// Returns `None` if the public key cannot be decoded or is the identity.
+    fn new(scheme: DrandScheme, raw_public_key: &[u8]) -> Option<Self> {
+        match scheme {
+            DrandScheme::Chained | DrandScheme::Unchained => {
+                let public_key = decode_g1(raw_public_key, BLSError::InvalidPublicKeyEncoding)
+                    .ok()
+                    .filter(|p| !bool::from(p.is_identity()))?;
+                Some(BeaconVerifier::G2Signatures(scheme, public_key))
+            }
+            DrandScheme::UnchainedG1 => {
+                let public_key = decode_g2(raw_public_key, BLSError::InvalidPublicKeyEncoding)
+                    .ok()
+                    .filter(|p| !bool::from(p.is_identity()))?;
+                Some(BeaconVerifier::G1Signatures(G2Prepared::from(public_key)))
+            }
+        }
+    }
+
+    fn verify(&self, round: u64, previous_signature: &[u8], signature: &[u8]) -> bool {
+        match self {
+            // e(pk, hash(message)) == e(g1, signature)
+            BeaconVerifier::G2Signatures(scheme, public_key) => {
+                let Ok(signature) = decode_g2(signature, BLSError::InvalidSignatureEncoding) else {
+                    return false;
+                };
+                let message = scheme.message(round, previous_signature);
+                let hash = G2Projective::hash_to_curve(&message, G2_DST, &[]).to_affine();
+                let g1_neg = -G1Affine::generator();
+
+                Bls12::multi_miller_loop(&[
+                    (public_key, &G2Prepared::from(hash)),
+                    (&g1_neg, &G2Prepared::from(signature)),
+                ])
+                .final_exponentiation()
+                    == Gt::identity()
+            }
+            // e(hash(message), pk) == e(signature, g2)
+            BeaconVerifier::G1Signatures(public_key) => {
+                let Ok(signature) = decode_g1(signature, BLSError::InvalidSignatureEncoding) else {
+                    return false;
+                };
+                let message = DrandScheme::UnchainedG1.message(round, &[]);
+                let hash = G1Projective::hash_to_curve(&message, G1_DST, &[]).to_affine();
+                let g2 = G2Prepared::from(G2Affine::generator());
+
+                Bls12::multi_miller_loop(&[(&hash, public_key), (&-signature, &g2)])
+                    .final_exponentiation()
+                    == Gt::identity()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blstrs::Scalar;
+    use group::ff::Field;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn verify(
+        scheme: DrandScheme,
+        public_key: &[u8],
+        round: u64,
+        previous_signature: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        drand_verify_beacon(
+            scheme,
+            public_key.into(),
+            round,
+            previous_signature.into(),
+            signature.into(),
+        )
+    }
+
+    #[test]
+    fn chained_mainnet() {
+        let public_key = unhex("868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31");
+        let previous = unhex("a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747");
+        let signature = unhex("82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42");
+        let round = 72785;
+
+        assert!(verify(
+            DrandScheme::Chained,
+            &public_key,
+            round,
+            &previous,
+            &signature
+        ));
+        assert!(!verify(
+            DrandScheme::Chained,
+            &public_key,
+            round - 1,
+            &previous,
+            &signature
+        ));
+        assert!(!verify(
+            DrandScheme::Unchained,
+            &public_key,
+            round,
+            &previous,
+            &signature
+        ));
+
+        let mut corrupted = previous.clone();
+        corrupted[0] ^= 1;
+        assert!(!verify(
+            DrandScheme::Chained,
+            &public_key,
+            round,
+            &corrupted,
+            &signature
+        ));
+
+        let result = drand_verify_beacons(
+            DrandScheme::Chained,
+            public_key[..].into(),
+            round,
+            previous[..].into(),
+            signature[..].into(),
+        );
+        assert!(result.valid);
+    }
+
+    #[test]
+    fn unchained_testnet() {
+        let public_key = unhex("8200fc249deb0148eb918d6e213980c5d01acd7fc251900d9260136da3b54836ce125172399ddc69c4e3e11429b62c11");
+        let signature = unhex("94f6b85df7cce7237e8e7df66d794ddad092de5d8bb6a791b97e905aa89852e506ac36a792eba7021e22eebf34891f8914bf9a8dd9233ea0a4c5ca00ef8404999f899073dd2eade61fe54077fee8168f83dcb61a758b6883b38904054e64a433");
+        let round = 223344;
+
+        assert!(verify(
+            DrandScheme::Unchained,
+            &public_key,
+            round,
+            &[],
+            &signature
+        ));
+        assert!(!verify(
+            DrandScheme::Unchained,
+            &public_key,
+            round - 1,
+            &[],
+            &signature
+        ));
+    }
+
+    #[test]
+    fn quicknet() {
+        let public_key = unhex("83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a");
+        let signature = unhex("b75c69d0b72a5d906e854e808ba7e2accb1542ac355ae486d591aa9d43765482e26cd02df835d3546d23c4b13e0dfc92");
+        let round = 123;
+
+        assert!(verify(
+            DrandScheme::UnchainedG1,
+            &public_key,
+            round,
+            &[],
+            &signature
+        ));
+        assert!(!verify(
+            DrandScheme::UnchainedG1,
+            &public_key,
+            round + 1,
+            &[],
+            &signature
+        ));
+
+        // garbage
+        assert!(!verify(
+            DrandScheme::UnchainedG1,
+            &public_key[..48],
+            round,
+            &[],
+            &signature
+        ));
+        assert!(!verify(
+            DrandScheme::UnchainedG1,
+            &public_key,
+            round,
+            &[],
+            &signature[..47]
+        ));
+    }
+
+    fn test_public_key(scheme: DrandScheme, secret: Scalar) -> Vec<u8> {
+        match scheme {
+            DrandScheme::UnchainedG1 => (G2Projective::generator() * secret)
+                .to_affine()
+                .to_compressed()
+                .to_vec(),
+            _ => (G1Projective::generator() * secret)
+                .to_affine()
+                .to_compressed()
+                .to_vec(),
+        }
+    }
+
+    fn test_sign(scheme: DrandScheme, secret: Scalar, message: &[u8]) -> Vec<u8> {
+        match scheme {
+            DrandScheme::UnchainedG1 => (G1Projective::hash_to_curve(message, G1_DST, &[])
+                * secret)
+                .to_affine()
+                .to_compressed()
+                .to_vec(),
+            _ => (G2Projective::hash_to_curve(message, G2_DST, &[]) * secret)
+                .to_affine()
+                .to_compressed()
+                .to_vec(),
+        }
+    }
+
+    #[test]
+    fn batch_verification() {
+        let rng = &mut ChaChaRng::seed_from_u64(12);
+        let secret = Scalar::random(rng);
+        let first_round = 1000;
+        let count = 20;
+
+        for scheme in [
+            DrandScheme::Chained,
+            DrandScheme::Unchained,
+            DrandScheme::UnchainedG1,
+        ] {
+            let public_key = test_public_key(scheme, secret);
+
+            let mut previous = vec![7u8; scheme.signature_bytes()];
+            let genesis = previous.clone();
+            let mut flattened = Vec::new();
+            for round in first_round..first_round + count {
+                let signature = test_sign(scheme, secret, &scheme.message(round, &previous));
+                flattened.extend_from_slice(&signature);
+                previous = signature;
+            }
+
+            let batch = |first_round: u64, flattened: &[u8]| {
+                drand_verify_beacons(
+                    scheme,
+                    public_key[..].into(),
+                    first_round,
+                    genesis[..].into(),
+                    flattened.into(),
+                )
+            };
+
+            let result = batch(first_round, &flattened);
+            assert!(result.valid, "{:?}", scheme);
+
+            let result = batch(first_round + 1, &flattened);
+            assert!(!result.valid);
+            assert_eq!(result.first_invalid_index, 0);
+
+            let size = scheme.signature_bytes();
+            let mut corrupted = flattened.clone();
+            corrupted[size * 7..size * 8].copy_from_slice(&flattened[..size]);
+            let result = batch(first_round, &corrupted);
+            assert!(!result.valid);
+            assert_eq!(result.first_invalid_index, 7);
+
+            let result = batch(first_round, &flattened[..flattened.len() - 1]);
+            assert!(!result.valid);
+            assert_eq!(result.first_invalid_index, count - 1);
+
+            let result = batch(first_round, &[]);
+            assert!(!result.valid);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use bls_signatures::{PrivateKey, Serialize};
+use rand::rngs::OsRng;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use safer_ffi::prelude::*;
+
+use super::api::{
+    decode_private_key, to_response, BLSPrivateKey, BLSPublicKey, BLSSignature, PUBLIC_KEY_BYTES,
+    SIGNATURE_BYTES,
+};
+use super::types::*;
+use crate::destructor;
+use crate::util::types::catch_panic_response_raw;
+
+impl InnerBLSKeyHandle {
+    pub(super) fn new(private_key: &PrivateKey) -> Self {
+        let mut handle = InnerBLSKeyHandle::default();
+        private_key
+            .write_bytes(&mut &mut handle.private_key[..])
+            .expect("preallocated");
+        handle
+    }
+
+    /// Rebuilds the private key from the bytes of the handle. Only the bytes are kept, as
+    /// `PrivateKey` is `Copy` and is not wiped, so the key is rebuilt right before each use.
+    pub(super) fn private_key(&self) -> PrivateKey {
+        PrivateKey::from_bytes(&self.private_key[..]).expect("validated on creation")
+    }
+}
+
+/// Generate a new private key and return a handle to it
+#[ffi_export]
+pub fn bls_key_handle_generate() -> repr_c::Box<InnerBLSKeyHandle> {
+    Box::new(InnerBLSKeyHandle::new(&PrivateKey::generate(&mut OsRng))).into()
+}
+
+/// Generate a new private key with seed and return a handle to it
+///
+/// Derives the same key as `private_key_generate_with_seed`.
+///
+/// **Warning**: Use this function only for testing or with very secure seeds
+///
+/// # Arguments
+///
+/// * `raw_seed` - a seed byte array with 32 bytes
+#[ffi_export]
+pub fn bls_key_handle_from_seed(raw_seed: &[u8; 32]) -> repr_c::Box<InnerBLSKeyHandle> {
+    let rng = &mut ChaChaRng::from_seed(*raw_seed);
+
+    Box::new(InnerBLSKeyHandle::new(&PrivateKey::generate(rng))).into()
+}
+
+/// Create a handle from the bytes of a private key
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+#[ffi_export]
+pub fn bls_key_handle_from_bytes(
+    raw_private_key: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSKeyHandleResponse> {
+    catch_panic_response_raw("bls_key_handle_from_bytes", || {
+        to_response(
+            decode_private_key(&raw_private_key)
+                .map(|private_key| Some(Box::new(InnerBLSKeyHandle::new(&private_key)).into())),
+        )
+    })
+}
+
+/// Sign a message with the private key of a handle and return the signature
+///
+/// # Arguments
+///
+/// * `handle`  - key handle
+/// * `message` - message byte array
+#[ffi_export]
+pub fn bls_key_handle_sign(
+    handle: &InnerBLSKeyHandle,
+    message: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSSignature> {
+    let mut raw_signature: BLSSignature = [0; SIGNATURE_BYTES];
+    handle
+        .private_key()
+        .sign(&message[..])
+        .write_bytes(&mut raw_signature.as_mut())
+        .expect("preallocated");
+
+    Box::new(raw_signature).into()
+}
+
+/// Generate the public key for the private key of a handle
+///
+/// # Arguments
+///
+/// * `handle` - key handle
+#[ffi_export]
+pub fn bls_key_handle_public_key(handle: &InnerBLSKeyHandle) -> repr_c::Box<BLSPublicKey> {
+    let mut raw_public_key: BLSPublicKey = [0; PUBLIC_KEY_BYTES];
+    handle
+        .private_key()
+        .public_key()
+        .write_bytes(&mut raw_public_key.as_mut())
+        .expect("preallocated");
+
+    Box::new(raw_public_key).into()
+}
+
+/// Export the private key of a handle
+///
+/// The returned bytes are a copy, callers are responsible for wiping them.
+///
+/// # Arguments
+///
+/// * `handle` - key handle
+#[ffi_export]
+pub fn bls_key_handle_export(handle: &InnerBLSKeyHandle) -> repr_c::Box<BLSPrivateKey> {
+    let raw_private_key: BLSPrivateKey = *handle.private_key;
+
+    Box::new(raw_private_key).into()
+}
+
+destructor!(drop_bls_key_handle, InnerBLSKeyHandle);
+destructor!(destroy_bls_key_handle_response, BLSKeyHandleResponse);
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::PRIVATE_KEY_BYTES;
+    use super::super::api::{
+        hash, private_key_generate, private_key_generate_with_seed, private_key_public_key,
+        private_key_sign, verify,
+    };
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    #[test]
+    fn key_handle() {
+        let private_key = private_key_generate();
+        let response = bls_key_handle_from_bytes(private_key[..].into());
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        let handle = response.value.as_ref().unwrap();
+
+        assert_eq!(&bls_key_handle_export(handle)[..], &private_key[..]);
+
+        let public_key = bls_key_handle_public_key(handle);
+        assert_eq!(
+            &public_key[..],
+            &private_key_public_key(private_key[..].into()).unwrap()[..]
+        );
+
+        let message = b"hello world";
+        let signature = bls_key_handle_sign(handle, message[..].into());
+        assert_eq!(
+            &signature[..],
+            &private_key_sign(private_key[..].into(), message[..].into()).unwrap()[..]
+        );
+        let digest = hash(message[..].into());
+        assert!(verify(
+            signature[..].into(),
+            digest[..].into(),
+            public_key[..].into()
+        ));
+
+        let response = bls_key_handle_from_bytes(private_key[..PRIVATE_KEY_BYTES - 1].into());
+        assert_eq!(response.error_code, BLSError::InvalidPrivateKey as i32);
+        assert!(response.value.is_none());
+    }
+
+    #[test]
+    fn key_handle_with_seed() {
+        let seed = [7u8; 32];
+        let handle = bls_key_handle_from_seed(&seed);
+
+        assert_eq!(
+            &bls_key_handle_export(&handle)[..],
+            &private_key_generate_with_seed(&seed)[..]
+        );
+        assert_ne!(
+            &bls_key_handle_export(&bls_key_handle_generate())[..],
+            &bls_key_handle_export(&handle)[..]
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Password protected keystores as specified by
+//! [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335).
+
+use aes::Aes128;
+use bls_signatures::{PrivateKey, Serialize as _};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use safer_ffi::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use super::api::{decode_private_key, to_response, PRIVATE_KEY_BYTES};
+use super::types::*;
+use crate::util::types::catch_panic_response_raw;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+const VERSION: u32 = 4;
+const DECRYPTION_KEY_BYTES: usize = 32;
+const SALT_BYTES: usize = 32;
+const IV_BYTES: usize = 16;
+const SCRYPT_N: u32 = 1 << 18;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const PBKDF2_C: u32 = 1 << 18;
+/// Limits of the key derivation parameters of keystores, so a keystore can't make decryption
+/// take forever or exhaust the memory.
+const SCRYPT_MAX_N: u32 = 1 << 20;
+const SCRYPT_MAX_R_P: u32 = 16;
+const PBKDF2_MAX_C: u32 = 1 << 24;
+const PBKDF2_PRF: &str = "hmac-sha256";
+
+/// Encrypt a private key into a JSON keystore
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+/// * `password`        - UTF-8 password, normalized as specified by EIP-2335
+/// * `kdf`             - key derivation function that protects the private key
+#[ffi_export]
+pub fn bls_keystore_encrypt(
+    raw_private_key: c_slice::Ref<'_, u8>,
+    password: c_slice::Ref<'_, u8>,
+    kdf: BLSKeystoreKdf,
+) -> repr_c::Box<BLSKeystoreResponse> {
+    catch_panic_response_raw("bls_keystore_encrypt", || {
+        to_response(
+            decode_private_key(&raw_private_key)
+                .and_then(|private_key| encrypt(&private_key, &password, kdf))
+                .map(|keystore| keystore.into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Decrypt the private key of a JSON keystore
+///
+/// Fails with `InvalidPassword` if the password is wrong and with `InvalidKeystore` if the
+/// keystore is malformed, or its key derivation is more expensive than scrypt with `n = 2^20` and
+/// `r * p = 16`, or pbkdf2 with `c = 2^24`.
+///
+/// # Arguments
+///
+/// * `keystore` - JSON keystore
+/// * `password` - UTF-8 password
+#[ffi_export]
+pub fn bls_keystore_decrypt(
+    keystore: c_slice::Ref<'_, u8>,
+    password: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSPrivateKeyResponse> {
+    catch_panic_response_raw("bls_keystore_decrypt", || {
+        to_response(
+            decrypt(&keystore, &password)
+                .map(|private_key| private_key.as_bytes().into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Same as [`bls_keystore_encrypt`], but encrypts the private key of a handle.
+#[ffi_export]
+pub fn bls_key_handle_to_keystore(
+    handle: &InnerBLSKeyHandle,
+    password: c_slice::Ref<'_, u8>,
+    kdf: BLSKeystoreKdf,
+) -> repr_c::Box<BLSKeystoreResponse> {
+    catch_panic_response_raw("bls_key_handle_to_keystore", || {
+        to_response(
+            encrypt(&handle.private_key(), &password, kdf)
+                .map(|keystore| keystore.into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Same as [`bls_keystore_decrypt`], but returns a handle to the private key, so it never has to
+/// leave rust owned memory.
+#[ffi_export]
+pub fn bls_key_handle_from_keystore(
+    keystore: c_slice::Ref<'_, u8>,
+    password: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<BLSKeyHandleResponse> {
+    catch_panic_response_raw("bls_key_handle_from_keystore", || {
+        to_response(
+            decrypt(&keystore, &password)
+                .map(|private_key| Some(Box::new(InnerBLSKeyHandle::new(&private_key)).into())),
+        )
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    crypto: Crypto,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    pubkey: String,
+    #[serde(default)]
+    path: String,
+    uuid: String,
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Crypto {
+    kdf: Module<KdfParams>,
+    checksum: Module<EmptyParams>,
+    cipher: Module<CipherParams>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Module<P> {
+    function: String,
+    params: P,
+    message: String,
+}
+
+/// Parameters of both key derivation functions, which one applies depends on the function.
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    dklen: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    r: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    c: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prf: Option<String>,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EmptyParams {}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+impl KdfParams {
+    fn new(kdf: BLSKeystoreKdf, salt: &[u8]) -> Module<Self> {
+        let (function, params) = match kdf {
+            BLSKeystoreKdf::Scrypt => (
+                "scrypt",
+                KdfParams {
+                    dklen: DECRYPTION_KEY_BYTES,
+                    n: Some(SCRYPT_N),
+                    r: Some(SCRYPT_R),
+                    p: Some(SCRYPT_P),
+                    c: None,
+                    prf: None,
+                    salt: hex::encode(salt),
+                },
+            ),
+            BLSKeystoreKdf::Pbkdf2 => (
+                "pbkdf2",
+                KdfParams {
+                    dklen: DECRYPTION_KEY_BYTES,
+                    n: None,
+                    r: None,
+                    p: None,
+                    c: Some(PBKDF2_C),
+                    prf: Some(PBKDF2_PRF.into()),
+                    salt: hex::encode(salt),
+                },
+            ),
+        };
+
+        Module {
+            function: function.into(),
+            params,
+            message: String::new(),
+        }
+    }
+}
+
+fn encrypt(
+    private_key: &PrivateKey,
+    password: &[u8],
+    kdf: BLSKeystoreKdf,
+) -> Result<Vec<u8>, BLSError> {
+    let mut salt = [0u8; SALT_BYTES];
+    OsRng.fill_bytes(&mut salt);
+
+    encrypt_with(private_key, password, KdfParams::new(kdf, &salt))
+}
+
+fn encrypt_with(
+    private_key: &PrivateKey,
+    password: &[u8],
+    kdf: Module<KdfParams>,
+) -> Result<Vec<u8>, BLSError> {
+    let decryption_key = derive_decryption_key(&kdf, &normalize_password(password)?)?;
+    let mut iv = [0u8; IV_BYTES];
+    OsRng.fill_bytes(&mut iv);
+
+    // the secret is the big endian private key, while it serializes as little endian
+    let mut secret = Zeroizing::new(private_key.as_bytes());
+    secret.reverse();
+    let mut cipher_message = secret.to_vec();
+    Aes128Ctr::new(decryption_key[..16].into(), &iv.into()).apply_keystream(&mut cipher_message);
+
+    let keystore = Keystore {
+        crypto: Crypto {
+            kdf,
+            checksum: Module {
+                function: "sha256".into(),
+                params: EmptyParams {},
+                message: hex::encode(checksum(&decryption_key, &cipher_message)),
+            },
+            cipher: Module {
+                function: "aes-128-ctr".into(),
+                params: CipherParams {
+                    iv: hex::encode(iv),
+                },
+                message: hex::encode(&cipher_message),
+            },
+        },
+        description: String::new(),
+        pubkey: hex::encode(private_key.public_key().as_bytes()),
+        path: String::new(),
+        uuid: uuid::Uuid::new_v4().to_string(),
+        version: VERSION,
+    };
+
+    Ok(serde_json::to_vec(&keystore).expect("serializable"))
+}
+
+fn decrypt(raw_keystore: &[u8], password: &[u8]) -> Result<PrivateKey, BLSError> {
+    let keystore: Keystore =
+        serde_json::from_slice(raw_keystore).map_err(|_| BLSError::InvalidKeystore)?;
+    let crypto = &keystore.crypto;
+    if keystore.version != VERSION
+        || crypto.checksum.function != "sha256"
+        || crypto.cipher.function != "aes-128-ctr"
+    {
+        return Err(BLSError::InvalidKeystore);
+    }
+    let cipher_message = unhex(&crypto.cipher.message)?;
+    let iv: [u8; IV_BYTES] = unhex(&crypto.cipher.params.iv)?
+        .try_into()
+        .map_err(|_| BLSError::InvalidKeystore)?;
+    if cipher_message.len() != PRIVATE_KEY_BYTES {
+        return Err(BLSError::InvalidKeystore);
+    }
+
+    let decryption_key = derive_decryption_key(&crypto.kdf, &normalize_password(password)?)?;
+    if checksum(&decryption_key, &cipher_message)[..] != unhex(&crypto.checksum.message)?[..] {
+        return Err(BLSError::InvalidPassword);
+    }
+
+    let mut secret = Zeroizing::new(cipher_message);
+    Aes128Ctr::new(decryption_key[..16].into(), &iv.into()).apply_keystream(&mut secret);
+    secret.reverse();
+    let private_key = decode_private_key(&secret).map_err(|_| BLSError::InvalidKeystore)?;
+
+    // the public key is optional, but has to match if present
+    if !keystore.pubkey.is_empty()
+        && unhex(&keystore.pubkey)? != private_key.public_key().as_bytes()
+    {
+        return Err(BLSError::InvalidKeystore);
+    }
+
+    Ok(private_key)
+}
+
+fn derive_decryption_key(
+    kdf: &Module<KdfParams>,
+    password: &[u8],
+) -> Result<Zeroizing<[u8; DECRYPTION_KEY_BYTES]>, BLSError> {
+    let params = &kdf.params;
+    if params.dklen != DECRYPTION_KEY_BYTES {
+        return Err(BLSError::InvalidKeystore);
+    }
+    let salt = unhex(&params.salt)?;
+
+    let mut decryption_key = Zeroizing::new([0u8; DECRYPTION_KEY_BYTES]);
+    match kdf.function.as_str() {
+        "scrypt" => {
+            let (Some(n), Some(r), Some(p)) = (params.n, params.r, params.p) else {
+                return Err(BLSError::InvalidKeystore);
+            };
+            if !n.is_power_of_two() || !(2..=SCRYPT_MAX_N).contains(&n) {
+                return Err(BLSError::InvalidKeystore);
+            }
+            if u64::from(r) * u64::from(p) > u64::from(SCRYPT_MAX_R_P) {
+                return Err(BLSError::InvalidKeystore);
+            }
+            let params = scrypt::Params::new(n.ilog2() as u8, r, p, DECRYPTION_KEY_BYTES)
+                .map_err(|_| BLSError::InvalidKeystore)?;
+            scrypt::scrypt(password, &salt, &params, &mut decryption_key[..])
+                .expect("valid length");
+        }
+        "pbkdf2" => {
+            let Some(c) = params.c else {
+                return Err(BLSError::InvalidKeystore);
+            };
+            if params.prf.as_deref() != Some(PBKDF2_PRF) || !(1..=PBKDF2_MAX_C).contains(&c) {
+                return Err(BLSError::InvalidKeystore);
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(password, &salt, c, &mut decryption_key[..]);
+        }
+        _ => return Err(BLSError::InvalidKeystore),
+    }
+
+    Ok(decryption_key)
+}
+
+fn checksum(decryption_key: &[u8; DECRYPTION_KEY_BYTES], cipher_message: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(&decryption_key[16..])
+        .chain_update(cipher_message)
+        .finalize()
+        .into()
+}
+
+/// NFKD normalizes the password and strips the C0, C1 and delete control codes.
+fn normalize_password(password: &[u8]) -> Result<Zeroizing<Vec<u8>>, BLSError> {
+    let password = std::str::from_utf8(password).map_err(|_| BLSError::InvalidPassword)?;
+    let normalized: String = password.nfkd().filter(|c| !c.is_control()).collect();
+
+    Ok(Zeroizing::new(normalized.into_bytes()))
+}
+
+fn unhex(s: &str) -> Result<Vec<u8>, BLSError> {
+    hex::decode(s).map_err(|_| BLSError::InvalidKeystore)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::private_key_generate;
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+    const SECRET: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+
+    // Test vectors from EIP-2335.
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "scrypt",
+                "params": {
+                    "dklen": 32,
+                    "n": 262144,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "d2217fe5f3e9a1e34581ef8a78f7c9928e436d36dacc5e846690a5581e8ea484"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "06ae90d55fe0a6e9c5c3bc5b170827b2e5cce3929ed3f116c2811e6366dfe20f"
+            }
+        },
+        "description": "This is a test keystore that uses scrypt to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/3141592653/589793238",
+        "uuid": "1d85ae20-35c5-4611-98e8-aa14a633906f",
+        "version": 4
+    }"#;
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#;
+
+    /// Cheap key derivation parameters, so the round trips don't take long.
+    fn light_kdf(kdf: BLSKeystoreKdf) -> Module<KdfParams> {
+        let mut module = KdfParams::new(kdf, &[1; SALT_BYTES]);
+        match kdf {
+            BLSKeystoreKdf::Scrypt => module.params.n = Some(1 << 4),
+            BLSKeystoreKdf::Pbkdf2 => module.params.c = Some(16),
+        }
+        module
+    }
+
+    fn check_test_vector(keystore: &str) {
+        let mut expected = hex::decode(SECRET).unwrap();
+        expected.reverse();
+
+        let private_key =
+            bls_keystore_decrypt(keystore.as_bytes().into(), PASSWORD.as_bytes().into());
+        assert_eq!(private_key.status_code, FCPResponseStatus::NoError);
+        assert_eq!(&private_key.value[..], &expected[..]);
+    }
+
+    #[test]
+    fn eip2335_pbkdf2_test_vector() {
+        check_test_vector(PBKDF2_KEYSTORE);
+    }
+
+    // Full strength scrypt takes about a minute in debug builds.
+    #[test]
+    #[ignore]
+    fn eip2335_scrypt_test_vector() {
+        check_test_vector(SCRYPT_KEYSTORE);
+    }
+
+    #[test]
+    fn round_trip() {
+        let raw_private_key = private_key_generate();
+        let private_key = decode_private_key(&raw_private_key[..]).unwrap();
+
+        for kdf in [BLSKeystoreKdf::Scrypt, BLSKeystoreKdf::Pbkdf2] {
+            let keystore = encrypt_with(&private_key, b"pass\x7fword", light_kdf(kdf)).unwrap();
+
+            // control codes are stripped from passwords
+            let decrypted = bls_keystore_decrypt(keystore[..].into(), b"password"[..].into());
+            assert_eq!(decrypted.status_code, FCPResponseStatus::NoError);
+            assert_eq!(&decrypted.value[..], &raw_private_key[..]);
+            let parsed: Keystore = serde_json::from_slice(&keystore).unwrap();
+            assert_eq!(parsed.pubkey.len(), 2 * super::super::api::PUBLIC_KEY_BYTES);
+
+            let handle = bls_key_handle_from_keystore(keystore[..].into(), b"password"[..].into());
+            let handle = handle.value.as_ref().unwrap();
+            assert_eq!(&handle.private_key[..], &raw_private_key[..]);
+
+            let decrypted = bls_keystore_decrypt(keystore[..].into(), b"passw0rd"[..].into());
+            assert_eq!(decrypted.error_code, BLSError::InvalidPassword as i32);
+        }
+    }
+
+    #[test]
+    fn corrupt_keystores() {
+        let private_key = decode_private_key(&private_key_generate()[..]).unwrap();
+        let keystore =
+            encrypt_with(&private_key, b"password", light_kdf(BLSKeystoreKdf::Scrypt)).unwrap();
+        let keystore: serde_json::Value = serde_json::from_slice(&keystore).unwrap();
+
+        let corruptions: [fn(&mut serde_json::Value); 8] = [
+            |k| k["version"] = 3.into(),
+            |k| k["crypto"]["kdf"]["function"] = "argon2".into(),
+            |k| k["crypto"]["kdf"]["params"]["n"] = 1000.into(),
+            |k| k["crypto"]["kdf"]["params"]["n"] = (SCRYPT_MAX_N << 1).into(),
+            |k| k["crypto"]["kdf"]["params"]["p"] = 4.into(),
+            |k| k["crypto"]["cipher"]["params"]["iv"] = "00".into(),
+            |k| k["crypto"]["cipher"]["message"] = "zz".into(),
+            |k| k["pubkey"] = hex::encode([0xc0; 48]).into(),
+        ];
+        for corrupt in corruptions {
+            let mut corrupted = keystore.clone();
+            corrupt(&mut corrupted);
+            let corrupted = serde_json::to_vec(&corrupted).unwrap();
+            let decrypted = bls_keystore_decrypt(corrupted[..].into(), b"password"[..].into());
+            assert_eq!(decrypted.error_code, BLSError::InvalidKeystore as i32);
+        }
+
+        let decrypted = bls_keystore_decrypt(b"{}"[..].into(), b"password"[..].into());
+        assert_eq!(decrypted.error_code, BLSError::InvalidKeystore as i32);
+
+        let mut kdf = light_kdf(BLSKeystoreKdf::Pbkdf2);
+        kdf.params.c = Some(PBKDF2_MAX_C + 1);
+        assert!(matches!(
+            derive_decryption_key(&kdf, b"password"),
+            Err(BLSError::InvalidKeystore)
+        ));
+    }
+
+    #[test]
+    fn encrypt_with_standard_parameters() {
+        let scrypt = KdfParams::new(BLSKeystoreKdf::Scrypt, &[1; SALT_BYTES]);
+        assert_eq!(scrypt.function, "scrypt");
+        assert_eq!(
+            (scrypt.params.n, scrypt.params.r, scrypt.params.p),
+            (Some(SCRYPT_N), Some(SCRYPT_R), Some(SCRYPT_P))
+        );
+        let pbkdf2 = KdfParams::new(BLSKeystoreKdf::Pbkdf2, &[1; SALT_BYTES]);
+        assert_eq!(pbkdf2.function, "pbkdf2");
+        assert_eq!(pbkdf2.params.c, Some(PBKDF2_C));
+
+        let raw_private_key = private_key_generate();
+        let keystore = bls_keystore_encrypt(
+            raw_private_key[..31].into(),
+            b"password"[..].into(),
+            BLSKeystoreKdf::Scrypt,
+        );
+        assert_eq!(keystore.error_code, BLSError::InvalidPrivateKey as i32);
+    }
+}
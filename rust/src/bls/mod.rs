@@ -1 +1,10 @@
+pub mod aggregator;
 pub mod api;
+pub mod block;
+pub mod cache;
+pub mod derive;
+pub mod drand;
+pub mod handle;
+pub mod keystore;
+pub mod threshold;
+pub mod types;
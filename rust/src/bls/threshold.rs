@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+
+use bls_signatures::{PrivateKey, Serialize, Signature};
+use blstrs::{G2Projective, Scalar};
+use group::ff::Field;
+use rand::rngs::OsRng;
+use safer_ffi::prelude::*;
+
+use super::api::{
+    decode_private_key, decode_public_key, decode_signature, to_response, PRIVATE_KEY_BYTES,
+    SIGNATURE_BYTES,
+};
+use super::types::*;
+use crate::destructor;
+use crate::util::types::catch_panic_response_raw;
+
+pub const SHARE_INDEX_BYTES: usize = 4;
+pub const PARTIAL_SIGNATURE_BYTES: usize = SHARE_INDEX_BYTES + SIGNATURE_BYTES;
+/// Maximum number of shares a private key can be split into, which bounds the size of the
+/// response.
+pub const MAX_SHARES: u32 = 1 << 16;
+
+/// A signature by a private key share, prefixed with the big endian index of the share.
+pub type BLSPartialSignature = [u8; PARTIAL_SIGNATURE_BYTES];
+
+#[ffi_export]
+fn destroy_box_bls_partial_signature(ptr: repr_c::Box<BLSPartialSignature>) {
+    drop(ptr);
+}
+
+/// Split a private key into `shares` shares, any `threshold` of which can sign for it
+///
+/// The shares are evaluations of a random polynomial of degree `threshold - 1`, whose constant
+/// term is the private key (Shamir secret sharing). The public key of a share, as returned by
+/// `private_key_public_key`, verifies its partial signatures.
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+/// * `threshold`       - number of shares needed to sign
+/// * `shares`          - number of shares to generate, at most `MAX_SHARES`
+#[ffi_export]
+pub fn threshold_split_private_key(
+    raw_private_key: c_slice::Ref<'_, u8>,
+    threshold: u32,
+    shares: u32,
+) -> repr_c::Box<BLSPrivateKeySharesResponse> {
+    catch_panic_response_raw("threshold_split_private_key", || {
+        to_response(
+            split_inner(&raw_private_key, threshold, shares)
+                .map(|shares| shares.into_boxed_slice().into()),
+        )
+    })
+}
+
+/// Sign a message with a private key share and return the partial signature
+///
+/// # Arguments
+///
+/// * `raw_share`   - private key share byte array
+/// * `share_index` - index of the share, as assigned by `threshold_split_private_key`
+/// * `message`     - message byte array
+///
+/// Returns `None` when passed invalid arguments.
+#[ffi_export]
+pub fn threshold_partial_sign(
+    raw_share: c_slice::Ref<'_, u8>,
+    share_index: u32,
+    message: c_slice::Ref<'_, u8>,
+) -> Option<repr_c::Box<BLSPartialSignature>> {
+    if share_index == 0 {
+        return None;
+    }
+    let share = decode_private_key(&raw_share).ok()?;
+
+    let mut raw_partial: BLSPartialSignature = [0; PARTIAL_SIGNATURE_BYTES];
+    raw_partial[..SHARE_INDEX_BYTES].copy_from_slice(&share_index.to_be_bytes());
+    share
+        .sign(&message[..])
+        .write_bytes(&mut &mut raw_partial[SHARE_INDEX_BYTES..])
+        .expect("preallocated");
+
+    Some(Box::new(raw_partial).into())
+}
+
+/// Verify a partial signature against the public key of the share that produced it
+///
+/// # Arguments
+///
+/// * `partial_signature`    - partial signature byte array (PARTIAL_SIGNATURE_BYTES long)
+/// * `message`              - message byte array
+/// * `raw_share_public_key` - public key of the share (PUBLIC_KEY_BYTES long)
+#[ffi_export]
+pub fn threshold_partial_verify(
+    partial_signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    raw_share_public_key: c_slice::Ref<'_, u8>,
+) -> bool {
+    let Ok((_, signature)) = decode_partial_signature(&partial_signature) else {
+        return false;
+    };
+    let Ok(public_key) = decode_public_key(&raw_share_public_key) else {
+        return false;
+    };
+
+    public_key.verify(signature, &message[..])
+}
+
+/// Combine partial signatures into a signature of the private key that was split
+///
+/// The partial signatures are interpolated at zero, the result verifies with `verify` and
+/// `hash_verify` against the public key of the original private key.
+///
+/// # Arguments
+///
+/// * `flattened_partial_signatures` - partial signatures with distinct share indices
+/// * `threshold`                    - number of shares needed to sign, only the first
+///   `threshold` partial signatures are used
+#[ffi_export]
+pub fn threshold_combine(
+    flattened_partial_signatures: c_slice::Ref<'_, u8>,
+    threshold: u32,
+) -> repr_c::Box<BLSSignatureResponse> {
+    catch_panic_response_raw("threshold_combine", || {
+        to_response(
+            combine_inner(&flattened_partial_signatures, threshold)
+                .map(|s| s.as_bytes().into_boxed_slice().into()),
+        )
+    })
+}
+
+destructor!(
+    destroy_bls_private_key_shares_response,
+    BLSPrivateKeySharesResponse
+);
+
+fn split_inner(raw_private_key: &[u8], threshold: u32, shares: u32) -> Result<Vec<u8>, BLSError> {
+    let private_key = decode_private_key(raw_private_key)?;
+    if threshold == 0 || threshold > shares || shares > MAX_SHARES {
+        return Err(BLSError::InvalidThreshold);
+    }
+
+    let coefficients: Vec<Scalar> = std::iter::once(Scalar::from(private_key))
+        .chain((1..threshold).map(|_| Scalar::random(OsRng)))
+        .collect();
+
+    let mut raw_shares = Vec::with_capacity(shares as usize * PRIVATE_KEY_BYTES);
+    for index in 1..=shares {
+        let x = Scalar::from(u64::from(index));
+        // Horner's method
+        let share = coefficients
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, coefficient| acc * x + coefficient);
+        PrivateKey::from(share)
+            .write_bytes(&mut raw_shares)
+            .expect("vector grows");
+    }
+
+    Ok(raw_shares)
+}
+
+fn combine_inner(
+    flattened_partial_signatures: &[u8],
+    threshold: u32,
+) -> Result<Signature, BLSError> {
+    if !flattened_partial_signatures
+        .len()
+        .is_multiple_of(PARTIAL_SIGNATURE_BYTES)
+    {
+        return Err(BLSError::InvalidSignatureEncoding);
+    }
+    let partials = flattened_partial_signatures
+        .chunks(PARTIAL_SIGNATURE_BYTES)
+        .take(threshold as usize)
+        .map(decode_partial_signature)
+        .collect::<Result<Vec<_>, _>>()?;
+    if threshold == 0 || partials.len() < threshold as usize {
+        return Err(BLSError::InvalidThreshold);
+    }
+
+    let mut seen = HashSet::with_capacity(partials.len());
+    if !partials.iter().all(|(index, _)| seen.insert(*index)) {
+        return Err(BLSError::InvalidShareIndex);
+    }
+
+    let indices: Vec<Scalar> = partials
+        .iter()
+        .map(|(index, _)| Scalar::from(u64::from(*index)))
+        .collect();
+    let points: Vec<G2Projective> = partials.iter().map(|(_, s)| (*s).into()).collect();
+    let coefficients: Vec<Scalar> = (0..indices.len())
+        .map(|i| lagrange_at_zero(&indices, i))
+        .collect();
+
+    Ok(G2Projective::multi_exp(&points, &coefficients).into())
+}
+
+/// The Lagrange basis polynomial of `indices[i]`, evaluated at zero. The indices must be
+/// distinct.
+fn lagrange_at_zero(indices: &[Scalar], i: usize) -> Scalar {
+    let (numerator, denominator) = indices.iter().enumerate().filter(|(j, _)| *j != i).fold(
+        (Scalar::ONE, Scalar::ONE),
+        |(numerator, denominator), (_, x_j)| (numerator * x_j, denominator * (*x_j - indices[i])),
+    );
+
+    numerator * denominator.invert().expect("distinct indices")
+}
+
+fn decode_partial_signature(raw: &[u8]) -> Result<(u32, Signature), BLSError> {
+    if raw.len() != PARTIAL_SIGNATURE_BYTES {
+        return Err(BLSError::InvalidSignatureEncoding);
+    }
+    let index = u32::from_be_bytes(raw[..SHARE_INDEX_BYTES].try_into().expect("known size"));
+    if index == 0 {
+        return Err(BLSError::InvalidShareIndex);
+    }
+
+    Ok((index, decode_signature(&raw[SHARE_INDEX_BYTES..])?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::api::{hash, private_key_generate, private_key_public_key, verify};
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    fn share_public_key(raw_share: &[u8]) -> bls_signatures::PublicKey {
+        decode_private_key(raw_share).unwrap().public_key()
+    }
+
+    #[test]
+    fn split_sign_combine() {
+        let private_key = private_key_generate();
+        let public_key = private_key_public_key(private_key[..].into()).unwrap();
+        let message = b"threshold";
+        let digest = hash(message[..].into());
+
+        let shares = threshold_split_private_key(private_key[..].into(), 3, 5);
+        assert_eq!(shares.status_code, FCPResponseStatus::NoError);
+        assert_eq!(shares.value.len(), 5 * PRIVATE_KEY_BYTES);
+
+        let partials: Vec<BLSPartialSignature> = shares
+            .value
+            .chunks(PRIVATE_KEY_BYTES)
+            .zip(1u32..)
+            .map(|(share, index)| {
+                let partial = threshold_partial_sign(share.into(), index, message[..].into());
+                let partial = **partial.as_ref().unwrap();
+                let share_public_key = share_public_key(share).as_bytes();
+                assert!(threshold_partial_verify(
+                    partial[..].into(),
+                    message[..].into(),
+                    share_public_key[..].into(),
+                ));
+                assert!(!threshold_partial_verify(
+                    partial[..].into(),
+                    b"other"[..].into(),
+                    share_public_key[..].into(),
+                ));
+                partial
+            })
+            .collect();
+
+        // any three shares recover the signature of the private key
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let flattened: Vec<u8> = subset.iter().flat_map(|i| partials[*i]).collect();
+            let signature = threshold_combine(flattened[..].into(), 3);
+            assert_eq!(signature.status_code, FCPResponseStatus::NoError);
+            assert!(verify(
+                signature.value.as_ref(),
+                digest[..].into(),
+                public_key[..].into(),
+            ));
+        }
+
+        // two shares are not enough
+        let flattened: Vec<u8> = partials[..2].iter().flatten().copied().collect();
+        let signature = threshold_combine(flattened[..].into(), 3);
+        assert_eq!(signature.error_code, BLSError::InvalidThreshold as i32);
+        let signature = threshold_combine(flattened[..].into(), 2);
+        assert_eq!(signature.status_code, FCPResponseStatus::NoError);
+        assert!(!verify(
+            signature.value.as_ref(),
+            digest[..].into(),
+            public_key[..].into(),
+        ));
+
+        // duplicated shares
+        let flattened: Vec<u8> = [partials[0], partials[1], partials[0]]
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        let signature = threshold_combine(flattened[..].into(), 3);
+        assert_eq!(signature.error_code, BLSError::InvalidShareIndex as i32);
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        let private_key = private_key_generate();
+
+        let shares = threshold_split_private_key(private_key[..].into(), 0, 5);
+        assert_eq!(shares.error_code, BLSError::InvalidThreshold as i32);
+        let shares = threshold_split_private_key(private_key[..].into(), 6, 5);
+        assert_eq!(shares.error_code, BLSError::InvalidThreshold as i32);
+        let shares = threshold_split_private_key(private_key[..].into(), 1, MAX_SHARES + 1);
+        assert_eq!(shares.error_code, BLSError::InvalidThreshold as i32);
+        let shares = threshold_split_private_key(private_key[..31].into(), 1, 1);
+        assert_eq!(shares.error_code, BLSError::InvalidPrivateKey as i32);
+
+        // a 1-of-1 split is the private key itself
+        let shares = threshold_split_private_key(private_key[..].into(), 1, 1);
+        assert_eq!(&shares.value[..], &private_key[..]);
+
+        assert!(threshold_partial_sign(private_key[..].into(), 0, b"m"[..].into()).is_none());
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt;
+
+use blstrs::G2Projective;
+use safer_ffi::prelude::*;
+use zeroize::Zeroizing;
+
+use super::api::PRIVATE_KEY_BYTES;
+use crate::util::types::Result;
+
+/// Error codes reported in the `error_code` field of the BLS responses, in the `1..=999` range of
+/// BLS codes. These are used by both rust and go, so don't remove or renumber them.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BLSError {
+    /// A signature has the wrong length or is not a point on the curve.
+    InvalidSignatureEncoding = 1,
+    /// A public key has the wrong length or is not a point on the curve.
+    InvalidPublicKeyEncoding = 2,
+    /// A digest has the wrong length or is not a point on the curve.
+    InvalidDigestEncoding = 3,
+    /// A point is on the curve, but not in the prime order subgroup.
+    PointNotInSubgroup = 4,
+    /// A private key has the wrong length or is not a valid scalar.
+    InvalidPrivateKey = 5,
+    /// The number of digests or messages does not match the number of public keys.
+    CountMismatch = 6,
+    /// The message sizes don't fit the flattened messages.
+    MalformedMessages = 7,
+    /// Nothing was passed in where at least one element is needed.
+    EmptyInput = 8,
+    /// A threshold is zero or larger than the number of shares or partial signatures, or more
+    /// than `MAX_SHARES` shares are requested.
+    InvalidThreshold = 9,
+    /// A share index is zero or appears more than once.
+    InvalidShareIndex = 10,
+    /// A key derivation seed is shorter than 32 bytes.
+    InvalidSeed = 11,
+    /// A key derivation path is not of the form `m/<index>/<index>/...`.
+    InvalidDerivationPath = 12,
+    /// A public key or signature is the identity point.
+    IdentityPoint = 13,
+    /// A keystore is malformed or uses unsupported parameters.
+    InvalidKeystore = 14,
+    /// A keystore checksum doesn't match, the password is wrong.
+    InvalidPassword = 15,
+    /// A chain message is not a well formed CBOR encoded unsigned message.
+    InvalidMessageEncoding = 16,
+    /// A chain message appears more than once.
+    DuplicateMessage = 17,
+    /// The sender of a chain message is a BLS address of another public key.
+    SenderMismatch = 18,
+}
+
+impl fmt::Display for BLSError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            BLSError::InvalidSignatureEncoding => "invalid signature encoding",
+            BLSError::InvalidPublicKeyEncoding => "invalid public key encoding",
+            BLSError::InvalidDigestEncoding => "invalid digest encoding",
+            BLSError::PointNotInSubgroup => "point is not in the prime order subgroup",
+            BLSError::InvalidPrivateKey => "invalid private key",
+            BLSError::CountMismatch => "number of digests or messages and public keys differ",
+            BLSError::MalformedMessages => "message sizes exceed the flattened messages",
+            BLSError::EmptyInput => "empty input",
+            BLSError::InvalidThreshold => "invalid threshold",
+            BLSError::InvalidShareIndex => "share index is zero or duplicated",
+            BLSError::InvalidSeed => "seed must be at least 32 bytes",
+            BLSError::InvalidDerivationPath => "invalid derivation path",
+            BLSError::IdentityPoint => "point is the identity",
+            BLSError::InvalidKeystore => "invalid keystore",
+            BLSError::InvalidPassword => "invalid keystore password",
+            BLSError::InvalidMessageEncoding => "invalid message encoding",
+            BLSError::DuplicateMessage => "duplicate message",
+            BLSError::SenderMismatch => "sender address doesn't match the public key",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for BLSError {}
+
+// Dummy to make safer-ffi export the error enum
+#[ffi_export]
+fn dummy_bls_error(_error: BLSError) {
+    panic!("Don't call me");
+}
+
+/// Signature schemes of drand networks. These are used by both rust and go, so don't remove or
+/// renumber them.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DrandScheme {
+    /// `pedersen-bls-chained`: public keys on G1, signatures on G2 over
+    /// `sha256(previous_signature || round)`.
+    Chained = 0,
+    /// `pedersen-bls-unchained`: public keys on G1, signatures on G2 over `sha256(round)`.
+    Unchained = 1,
+    /// `bls-unchained-g1-rfc9380`, used by quicknet: public keys on G2, signatures on G1 over
+    /// `sha256(round)`.
+    UnchainedG1 = 2,
+}
+
+/// Key derivation functions that protect the secret of a keystore. These are used by both rust
+/// and go, so don't remove or renumber them.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BLSKeystoreKdf {
+    /// scrypt with `n = 2^18`, `r = 8` and `p = 1`.
+    Scrypt = 0,
+    /// PBKDF2-HMAC-SHA256 with `2^18` iterations.
+    Pbkdf2 = 1,
+}
+
+/// `value` is `true` if the signature is valid. A signature that is well formed but doesn't
+/// verify is not an error, the response has `NoError` status and `value` set to `false`.
+pub type BLSVerifyResponse = Result<bool>;
+
+/// `value` holds a signature (`SIGNATURE_BYTES` long).
+pub type BLSSignatureResponse = Result<c_slice::Box<u8>>;
+
+/// `value` holds a public key (`PUBLIC_KEY_BYTES` long).
+pub type BLSPublicKeyResponse = Result<c_slice::Box<u8>>;
+
+/// Has `NoError` status if the checked public key or signature is valid, the error code tells
+/// why it is not otherwise.
+pub type BLSValidateResponse = Result<()>;
+
+/// `value` holds a compressed or uncompressed G1 or G2 point.
+pub type BLSPointResponse = Result<c_slice::Box<u8>>;
+
+/// `value` holds a private key (`PRIVATE_KEY_BYTES` long).
+pub type BLSPrivateKeyResponse = Result<c_slice::Box<u8>>;
+
+/// `value` holds a JSON encoded EIP-2335 keystore.
+pub type BLSKeystoreResponse = Result<c_slice::Box<u8>>;
+
+/// `value` holds the flattened private key shares (`PRIVATE_KEY_BYTES` long each), the share at
+/// position `i` has the index `i + 1`.
+pub type BLSPrivateKeySharesResponse = Result<c_slice::Box<u8>>;
+
+/// A private key that stays in rust owned memory. The key bytes are zeroized when the handle is
+/// dropped.
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(Default)]
+pub struct InnerBLSKeyHandle {
+    pub(crate) private_key: Zeroizing<[u8; PRIVATE_KEY_BYTES]>,
+}
+
+pub type BLSKeyHandle = Option<repr_c::Box<InnerBLSKeyHandle>>;
+
+/// `value` holds the created key handle.
+pub type BLSKeyHandleResponse = Result<BLSKeyHandle>;
+
+/// A running aggregate of signatures.
+#[derive_ReprC]
+#[repr(opaque)]
+pub struct InnerBLSAggregator {
+    pub(crate) aggregate: G2Projective,
+    pub(crate) count: u64,
+}
@@ -0,0 +1,349 @@
+use anyhow::{anyhow, ensure};
+use k256::ecdsa::{Signature, VerifyingKey};
+use safer_ffi::prelude::*;
+use sha3::{Digest, Keccak256};
+
+use super::types::*;
+use crate::secp::api::recover_from_digest;
+use crate::util::types::catch_panic_response;
+
+/// Protocol byte of f4 (delegated) addresses.
+const DELEGATED_PROTOCOL: u8 = 4;
+/// Actor id of the Ethereum Address Manager, the namespace of f410 addresses.
+const EAM_ACTOR_ID: u8 = 10;
+/// Transaction type byte of EIP-1559 transactions.
+const EIP1559_TX_TYPE: u8 = 0x02;
+
+pub const ETH_ADDRESS_BYTES: usize = 20;
+/// Length of an f410 address in its byte representation: protocol, namespace and subaddress.
+pub const ADDRESS_BYTES: usize = 2 + ETH_ADDRESS_BYTES;
+
+/// Recover the f410 address of the sender of an Ethereum transaction
+///
+/// # Arguments
+///
+/// * `transaction` - the RLP encoded unsigned transaction, as it is signed by the sender. EIP-1559
+///   transactions are prefixed with their type byte (`0x02`), legacy transactions are a plain
+///   RLP list.
+/// * `signature`   - `r || s || v`, where `r` and `s` are 32 bytes each and `v` is the big endian
+///   recovery value. It is `0` or `1` for EIP-1559 transactions and `27`, `28` or
+///   `chain_id * 2 + 35 + {0, 1}` for legacy transactions, where the chain id must match the one
+///   of the transaction. Signatures with an `s` value above half the curve order are rejected,
+///   as by Ethereum.
+#[ffi_export]
+pub fn delegated_recover_address(
+    transaction: c_slice::Ref<'_, u8>,
+    signature: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<DelegatedAddressResponse> {
+    catch_panic_response("delegated_recover_address", || {
+        let address = recover_address(&transaction, &signature)?;
+
+        Ok(address.to_vec().into_boxed_slice().into())
+    })
+}
+
+/// Verify that an Ethereum transaction was signed by the owner of the given f410 address
+///
+/// # Arguments
+///
+/// * `transaction`      - the RLP encoded unsigned transaction, see `delegated_recover_address`
+/// * `signature`        - `r || s || v`, see `delegated_recover_address`
+/// * `expected_address` - byte representation of the f410 address (`ADDRESS_BYTES` long)
+#[ffi_export]
+pub fn delegated_verify(
+    transaction: c_slice::Ref<'_, u8>,
+    signature: c_slice::Ref<'_, u8>,
+    expected_address: c_slice::Ref<'_, u8>,
+) -> bool {
+    matches!(
+        recover_address(&transaction, &signature),
+        Ok(address) if address[..] == expected_address[..]
+    )
+}
+
+#[ffi_export]
+fn destroy_delegated_address_response(ptr: repr_c::Box<DelegatedAddressResponse>) {
+    drop(ptr);
+}
+
+fn recover_address(transaction: &[u8], signature: &[u8]) -> anyhow::Result<[u8; ADDRESS_BYTES]> {
+    ensure!(
+        signature.len() > 64 && signature.len() <= 64 + 8,
+        "invalid signature length {}",
+        signature.len()
+    );
+    let v = be_uint(&signature[64..]).expect("at most 8 bytes");
+
+    let recovery_id = match transaction.first() {
+        Some(&EIP1559_TX_TYPE) => {
+            ensure!(
+                is_rlp_list(&transaction[1..]),
+                "malformed EIP-1559 transaction"
+            );
+            ensure!(v <= 1, "invalid EIP-1559 signature recovery value {}", v);
+            v
+        }
+        Some(0xc0..=0xff) => {
+            ensure!(is_rlp_list(transaction), "malformed legacy transaction");
+            match v {
+                27 | 28 => v - 27,
+                35.. => {
+                    let chain_id = legacy_chain_id(transaction)
+                        .ok_or_else(|| anyhow!("legacy transaction without EIP-155 chain id"))?;
+                    ensure!(
+                        (v - 35) / 2 == chain_id,
+                        "signature chain id {} doesn't match transaction chain id {}",
+                        (v - 35) / 2,
+                        chain_id
+                    );
+                    (v - 35) % 2
+                }
+                _ => return Err(anyhow!("invalid legacy signature recovery value {}", v)),
+            }
+        }
+        _ => return Err(anyhow!("unsupported transaction type")),
+    };
+
+    // Ethereum rejects malleated signatures, unlike the FVM, which normalizes them
+    let sig = Signature::from_slice(&signature[..64])?;
+    ensure!(
+        sig.normalize_s().is_none(),
+        "signature s value is above half the curve order"
+    );
+
+    let mut recoverable = [0u8; 65];
+    recoverable[..64].copy_from_slice(&signature[..64]);
+    recoverable[64] = recovery_id as u8;
+
+    let digest = Keccak256::digest(transaction);
+    let public_key = recover_from_digest(&recoverable, &digest)
+        .ok_or_else(|| anyhow!("failed to recover public key from signature"))?;
+
+    Ok(delegated_address(&public_key))
+}
+
+/// The f410 address of an Ethereum account: the last 20 bytes of the keccak-256 hash of its
+/// uncompressed public key, in the EAM namespace.
+fn delegated_address(public_key: &VerifyingKey) -> [u8; ADDRESS_BYTES] {
+    let hash = Keccak256::digest(&public_key.to_encoded_point(false).as_bytes()[1..]);
+
+    let mut address = [0u8; ADDRESS_BYTES];
+    address[0] = DELEGATED_PROTOCOL;
+    address[1] = EAM_ACTOR_ID;
+    address[2..].copy_from_slice(&hash[hash.len() - ETH_ADDRESS_BYTES..]);
+    address
+}
+
+/// Checks that `bytes` is exactly one RLP list.
+fn is_rlp_list(bytes: &[u8]) -> bool {
+    matches!(rlp_item(bytes), Some((_, true, rest)) if rest.is_empty())
+}
+
+/// Returns the chain id of an EIP-155 legacy transaction, the 7th of its 9 fields, which is
+/// followed by two empty ones.
+fn legacy_chain_id(transaction: &[u8]) -> Option<u64> {
+    let (mut payload, true, _) = rlp_item(transaction)? else {
+        return None;
+    };
+
+    let mut fields = Vec::new();
+    while !payload.is_empty() {
+        let (field, false, rest) = rlp_item(payload)? else {
+            return None;
+        };
+        fields.push(field);
+        payload = rest;
+    }
+
+    match fields[..] {
+        [_, _, _, _, _, _, chain_id, r, s] if r.is_empty() && s.is_empty() => {
+            be_uint(chain_id).filter(|_| chain_id.first() != Some(&0))
+        }
+        _ => None,
+    }
+}
+
+/// Splits the first RLP item off `bytes`, returning its payload, whether it is a list and the
+/// bytes after it. Only canonical lengths are accepted.
+fn rlp_item(bytes: &[u8]) -> Option<(&[u8], bool, &[u8])> {
+    let (&prefix, rest) = bytes.split_first()?;
+    let (short_len, is_list) = match prefix {
+        0x00..=0x7f => return Some((&bytes[..1], false, rest)),
+        0x80..=0xbf => (prefix - 0x80, false),
+        0xc0..=0xff => (prefix - 0xc0, true),
+    };
+
+    let (len, rest) = if short_len <= 55 {
+        (usize::from(short_len), rest)
+    } else {
+        let len_bytes = usize::from(short_len - 55);
+        if rest.len() < len_bytes || rest[0] == 0 {
+            return None;
+        }
+        let (len, rest) = rest.split_at(len_bytes);
+        let len = usize::try_from(be_uint(len)?).ok()?;
+        if len < 56 {
+            return None;
+        }
+        (len, rest)
+    };
+
+    if rest.len() < len {
+        return None;
+    }
+    let (payload, rest) = rest.split_at(len);
+    Some((payload, is_list, rest))
+}
+
+/// Reads a big endian unsigned integer of at most 8 bytes.
+fn be_uint(bytes: &[u8]) -> Option<u64> {
+    if bytes.len() > 8 {
+        return None;
+    }
+
+    Some(
+        bytes
+            .iter()
+            .fold(0u64, |acc, byte| acc << 8 | u64::from(*byte)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn f410(eth_address: &str) -> Vec<u8> {
+        let mut address = vec![DELEGATED_PROTOCOL, EAM_ACTOR_ID];
+        address.extend(unhex(eth_address));
+        address
+    }
+
+    #[test]
+    fn legacy_transaction() {
+        // Example transaction from EIP-155, signed by the private key 0x4646..46.
+        let transaction = unhex(
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080",
+        );
+        let mut signature = unhex(concat!(
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+            "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        ));
+        signature.push(37);
+        let expected = f410("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+
+        let response = delegated_recover_address(transaction[..].into(), signature[..].into());
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        assert_eq!(&response.value[..], &expected[..]);
+
+        assert!(delegated_verify(
+            transaction[..].into(),
+            signature[..].into(),
+            expected[..].into(),
+        ));
+
+        let mut other = expected.clone();
+        other[ADDRESS_BYTES - 1] ^= 1;
+        assert!(!delegated_verify(
+            transaction[..].into(),
+            signature[..].into(),
+            other[..].into(),
+        ));
+
+        let mut tampered = transaction.clone();
+        tampered[1] ^= 1;
+        assert!(!delegated_verify(
+            tampered[..].into(),
+            signature[..].into(),
+            expected[..].into(),
+        ));
+
+        // the malleated signature, with s flipped to the upper half, is rejected
+        let sig = Signature::from_slice(&signature[..64]).unwrap();
+        let mut malleated = Signature::from_scalars(sig.r().to_bytes(), (-*sig.s()).to_bytes())
+            .unwrap()
+            .to_vec();
+        malleated.push(38);
+        let response = delegated_recover_address(transaction[..].into(), malleated[..].into());
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+
+        // the chain id of v must match the one of the transaction
+        let mut other_chain = signature.clone();
+        other_chain[64] = 39;
+        let response = delegated_recover_address(transaction[..].into(), other_chain[..].into());
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+        assert_eq!(legacy_chain_id(&transaction), Some(1));
+
+        // garbage
+        let response = delegated_recover_address(transaction[..].into(), signature[..64].into());
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+        let response = delegated_recover_address(
+            transaction[..transaction.len() - 1].into(),
+            signature[..].into(),
+        );
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+    }
+
+    #[test]
+    fn eip1559_transaction() {
+        let private_key = SigningKey::from_slice(&[0x46; 32]).unwrap();
+        // chain id 314, nonce 0, no access list
+        let mut transaction = vec![EIP1559_TX_TYPE];
+        transaction.extend(unhex(concat!(
+            "e1",
+            "82013a",
+            "80",
+            "01",
+            "02",
+            "825208",
+            "943535353535353535353535353535353535353535",
+            "01",
+            "80",
+            "c0",
+        )));
+
+        let (sig, recovery_id) = private_key
+            .sign_prehash_recoverable(&Keccak256::digest(&transaction))
+            .unwrap();
+        let mut signature = sig.to_vec();
+        signature.push(recovery_id.to_byte());
+        let expected = f410("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+
+        let response = delegated_recover_address(transaction[..].into(), signature[..].into());
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        assert_eq!(&response.value[..], &expected[..]);
+
+        // legacy recovery values are not allowed for typed transactions
+        signature[64] += 27;
+        assert!(!delegated_verify(
+            transaction[..].into(),
+            signature[..].into(),
+            expected[..].into(),
+        ));
+    }
+
+    #[cfg(feature = "fvm")]
+    #[test]
+    fn matches_fvm_address() {
+        use fvm4_shared::address::Address;
+
+        let public_key = SigningKey::from_slice(&[0x46; 32]).unwrap();
+        let address = delegated_address(public_key.verifying_key());
+        let expected = Address::new_delegated(
+            u64::from(EAM_ACTOR_ID),
+            &unhex("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"),
+        )
+        .unwrap();
+
+        assert_eq!(&address[..], &expected.to_bytes()[..]);
+    }
+}
@@ -0,0 +1,6 @@
+use safer_ffi::prelude::*;
+
+use crate::util::types::Result;
+
+/// `value` holds the byte representation of the sender's f410 address (`ADDRESS_BYTES` long).
+pub type DelegatedAddressResponse = Result<c_slice::Box<u8>>;
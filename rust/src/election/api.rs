@@ -0,0 +1,490 @@
+//! Winning PoSt eligibility, the VRF election proof and the win count derived from it, as
+//! implemented by Lotus.
+
+use anyhow::ensure;
+use bls_signatures::{PublicKey, Serialize, Signature};
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, Sign};
+use num_traits::{Euclid, Zero};
+use safer_ffi::prelude::*;
+
+use super::types::*;
+use crate::destructor;
+use crate::util::types::catch_panic_response;
+
+pub const VRF_DIGEST_BYTES: usize = 32;
+
+/// Fixed point precision of the win count computation, values are in Q.256 format.
+const PRECISION: usize = 256;
+/// Most blocks a miner can win in an epoch, as a multiple of the expected leaders.
+const MAX_WIN_COUNT_PER_LEADER: u64 = 3;
+
+pub type ElectionVrfDigest = [u8; VRF_DIGEST_BYTES];
+
+#[ffi_export]
+fn destroy_box_election_vrf_digest(ptr: repr_c::Box<ElectionVrfDigest>) {
+    drop(ptr);
+}
+
+lazy_static! {
+    // Coefficients of the rational approximation of `e^-x`, ordered from the highest order.
+    static ref EXP_NUM_COEF: Vec<BigInt> = parse_coefficients(&[
+        "-648770010757830093818553637600",
+        "67469480939593786226847644286976",
+        "-3197587544499098424029388939001856",
+        "89244641121992890118377641805348864",
+        "-1579656163641440567800982336819953664",
+        "17685496037279256458459817590917169152",
+        "-115682590513835356866803355398940131328",
+        "340282366920938463463374607431768211456",
+    ]);
+    static ref EXP_DENO_COEF: Vec<BigInt> = parse_coefficients(&[
+        "1225524182432722209606361",
+        "114095592300906098243859450",
+        "5665570424063336070530214243",
+        "194450132448609991765137938448",
+        "5068267641632683791026134915072",
+        "104716890604972796896895427629056",
+        "1748338658439454459487681798864896",
+        "23704654329841312470660182937960448",
+        "259380097567996910282699886670381056",
+        "2250336698853390384720606936038375424",
+        "14978272436876548034486263159246028800",
+        "72144088983913131323343765784380833792",
+        "224599776407103106596571252037123047424",
+        "340282366920938463463374607431768211456",
+    ]);
+}
+
+/// Verify a VRF election proof
+///
+/// The proof is a BLS signature of the miner worker over the VRF base, the election randomness
+/// drawn from the beacon, hashed with the Filecoin DST.
+///
+/// # Arguments
+///
+/// * `worker_public_key` - BLS public key of the miner worker
+/// * `vrf_base`          - the randomness the proof was produced over
+/// * `vrf_proof`         - the proof, a BLS signature
+#[ffi_export]
+pub fn election_verify_vrf(
+    worker_public_key: c_slice::Ref<'_, u8>,
+    vrf_base: c_slice::Ref<'_, u8>,
+    vrf_proof: c_slice::Ref<'_, u8>,
+) -> bool {
+    let Ok(public_key) = PublicKey::from_bytes(&worker_public_key) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_bytes(&vrf_proof) else {
+        return false;
+    };
+
+    public_key.verify(signature, &vrf_base[..])
+}
+
+/// Compute the VRF digest of an election proof, the blake2b-256 hash of the proof
+///
+/// # Arguments
+///
+/// * `vrf_proof` - the proof, a BLS signature
+#[ffi_export]
+pub fn election_vrf_digest(vrf_proof: c_slice::Ref<'_, u8>) -> repr_c::Box<ElectionVrfDigest> {
+    Box::new(vrf_digest(&vrf_proof)).into()
+}
+
+/// Compute how many blocks a miner may produce in an epoch
+///
+/// The VRF digest is compared against the inverse CDF of a Poisson distribution with rate
+/// `expected_leaders * miner_power / network_power`, using the same fixed point arithmetic as
+/// Lotus. The result is at most `3 * expected_leaders`.
+///
+/// # Arguments
+///
+/// * `vrf_proof`        - the election proof of the miner
+/// * `miner_power`      - quality adjusted power of the miner, big endian unsigned bytes
+/// * `network_power`    - quality adjusted power of the network, big endian unsigned bytes
+/// * `expected_leaders` - expected number of blocks per epoch
+#[ffi_export]
+pub fn election_compute_win_count(
+    vrf_proof: c_slice::Ref<'_, u8>,
+    miner_power: c_slice::Ref<'_, u8>,
+    network_power: c_slice::Ref<'_, u8>,
+    expected_leaders: u64,
+) -> repr_c::Box<ElectionWinCountResponse> {
+    catch_panic_response("election_compute_win_count", || {
+        let miner_power = BigInt::from_bytes_be(Sign::Plus, &miner_power);
+        let network_power = BigInt::from_bytes_be(Sign::Plus, &network_power);
+        ensure!(!network_power.is_zero(), "network power must not be zero");
+
+        Ok(win_count(
+            &vrf_proof,
+            &miner_power,
+            &network_power,
+            expected_leaders,
+        ))
+    })
+}
+
+destructor!(
+    destroy_election_win_count_response,
+    ElectionWinCountResponse
+);
+
+fn vrf_digest(vrf_proof: &[u8]) -> ElectionVrfDigest {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(VRF_DIGEST_BYTES)
+        .hash(vrf_proof);
+
+    hash.as_bytes().try_into().expect("known size")
+}
+
+fn win_count(
+    vrf_proof: &[u8],
+    miner_power: &BigInt,
+    network_power: &BigInt,
+    expected_leaders: u64,
+) -> u64 {
+    // the digest is read as a Q.256 number in [0, 1)
+    let digest = BigInt::from_bytes_be(Sign::Plus, &vrf_digest(vrf_proof));
+    let lambda = lambda(miner_power, network_power, expected_leaders);
+    let max_win_count = MAX_WIN_COUNT_PER_LEADER.saturating_mul(expected_leaders);
+
+    let mut poisson = Poisson::new(lambda);
+    let mut count = 0;
+    while digest < poisson.icdf && count < max_win_count {
+        poisson.next();
+        count += 1;
+    }
+
+    count
+}
+
+/// The rate of the Poisson distribution, `expected_leaders * miner_power / network_power` in
+/// Q.256 format.
+fn lambda(miner_power: &BigInt, network_power: &BigInt, expected_leaders: u64) -> BigInt {
+    ((miner_power * expected_leaders) << PRECISION) / network_power
+}
+
+/// Incremental inverse CDF of a Poisson distribution, `1 - P(X <= k)`.
+struct Poisson {
+    lambda: BigInt,
+    pmf: BigInt,
+    icdf: BigInt,
+    k: u64,
+}
+
+impl Poisson {
+    fn new(lambda: BigInt) -> Self {
+        // pmf(0) = e^-lambda
+        let pmf = expneg(&lambda);
+        let icdf = (BigInt::from(1) << PRECISION) - &pmf;
+
+        Poisson {
+            lambda,
+            pmf,
+            icdf,
+            k: 0,
+        }
+    }
+
+    /// Advances to the next `k`, using `pmf(k) = pmf(k - 1) * lambda / k`.
+    fn next(&mut self) {
+        self.k += 1;
+        self.pmf = ((&self.pmf / self.k) * &self.lambda) >> PRECISION;
+        self.icdf -= &self.pmf;
+    }
+}
+
+/// `e^-x` for `x` in Q.256 format, approximated by a rational function. It is most precise
+/// within `[0, 1.725)`, where the error is less than `3.4e-30`.
+fn expneg(x: &BigInt) -> BigInt {
+    let num = polyval(&EXP_NUM_COEF, x) << PRECISION;
+    let deno = polyval(&EXP_DENO_COEF, x);
+
+    num.div_euclid(&deno)
+}
+
+/// Evaluates a polynomial with Q.256 coefficients at `x` using Horner's method.
+fn polyval(coefficients: &[BigInt], x: &BigInt) -> BigInt {
+    coefficients[1..]
+        .iter()
+        .fold(coefficients[0].clone(), |acc, coefficient| {
+            ((acc * x) >> PRECISION) + coefficient
+        })
+}
+
+/// Parses integer coefficients, which are `2^128` times the actual coefficients, into Q.256.
+fn parse_coefficients(coefficients: &[&str]) -> Vec<BigInt> {
+    coefficients
+        .iter()
+        .map(|c| c.parse::<BigInt>().expect("valid coefficient") << (PRECISION - 128))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls::api::{private_key_generate, private_key_public_key, private_key_sign};
+    use crate::util::types::FCPResponseStatus;
+    use num_traits::ToPrimitive;
+
+    fn to_f64(q256: &BigInt) -> f64 {
+        q256.to_f64().unwrap() / 2f64.powi(PRECISION as i32)
+    }
+
+    #[test]
+    fn vrf_verification() {
+        let private_key = private_key_generate();
+        let public_key = private_key_public_key(private_key[..].into()).unwrap();
+        let vrf_base = [42u8; 32];
+        let vrf_proof = private_key_sign(private_key[..].into(), vrf_base[..].into()).unwrap();
+
+        assert!(election_verify_vrf(
+            public_key[..].into(),
+            vrf_base[..].into(),
+            vrf_proof[..].into(),
+        ));
+        assert!(!election_verify_vrf(
+            public_key[..].into(),
+            [43u8; 32][..].into(),
+            vrf_proof[..].into(),
+        ));
+        assert!(!election_verify_vrf(
+            public_key[..].into(),
+            vrf_base[..].into(),
+            vrf_proof[1..].into(),
+        ));
+
+        let digest = election_vrf_digest(vrf_proof[..].into());
+        assert_eq!(*digest, vrf_digest(&vrf_proof[..]));
+    }
+
+    #[test]
+    fn expneg_approximation() {
+        for i in 0..50 {
+            let x = f64::from(i) / 10.0;
+            let q256 = BigInt::from(i) * (BigInt::from(1) << PRECISION) / 10;
+            let error = (to_f64(&expneg(&q256)) - (-x).exp()).abs();
+            assert!(error < 1e-14, "e^-{x} is off by {error}");
+        }
+    }
+
+    #[test]
+    fn win_count_distribution() {
+        // a fifth of the power with five expected leaders wins one block per epoch on average
+        let (miner_power, network_power) = (BigInt::from(20), BigInt::from(100));
+        let samples = 20_000u32;
+
+        let mut histogram = [0u32; 16];
+        for i in 0..samples {
+            let count = win_count(&i.to_be_bytes(), &miner_power, &network_power, 5);
+            histogram[count as usize] += 1;
+        }
+        let mean = histogram
+            .iter()
+            .enumerate()
+            .map(|(count, n)| count as f64 * f64::from(*n))
+            .sum::<f64>()
+            / f64::from(samples);
+        assert!((mean - 1.0).abs() < 0.03, "mean win count {mean}");
+        let zero_wins = f64::from(histogram[0]) / f64::from(samples);
+        assert!((zero_wins - (-1f64).exp()).abs() < 0.02, "{zero_wins}");
+
+        // the win count is capped at three times the expected leaders
+        assert_eq!(win_count(&[], &network_power, &miner_power, 5), 15);
+        assert_eq!(win_count(&[], &BigInt::zero(), &network_power, 5), 0);
+    }
+
+    #[test]
+    fn win_count_ffi() {
+        let network_power = 25u128 << 60; // more than fits a u64
+        let response = election_compute_win_count(
+            b"proof"[..].into(),
+            network_power.to_be_bytes()[..].into(),
+            network_power.to_be_bytes()[..].into(),
+            5,
+        );
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        assert!(response.value > 0);
+
+        let response =
+            election_compute_win_count(b"proof"[..].into(), [1][..].into(), [0, 0][..].into(), 5);
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+    }
+
+    fn q256(value: &str) -> BigInt {
+        value.parse().unwrap()
+    }
+
+    // The vectors below are for the inputs of Lotus' `chain/types/electionproof_test.go` and must
+    // match it bit for bit, any difference changes who wins blocks.
+
+    #[test]
+    fn lotus_lambda_vectors() {
+        for (power, network_power, expected) in [
+            (
+                "10",
+                "100",
+                "57896044618658097711785492504343953926634992332820282019728792003956564819968",
+            ),
+            (
+                "1024",
+                "2048",
+                "289480223093290488558927462521719769633174961664101410098643960019782824099840",
+            ),
+            (
+                "2000000000000000",
+                "100000000000000000",
+                "11579208923731619542357098500868790785326998466564056403945758400791312963993",
+            ),
+            (
+                "64",
+                "128",
+                "289480223093290488558927462521719769633174961664101410098643960019782824099840",
+            ),
+        ] {
+            assert_eq!(
+                lambda(&q256(power), &q256(network_power), 5),
+                q256(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn lotus_expneg_vectors() {
+        // 256 points evenly spread over [0, 5]
+        let step = (BigInt::from(5) << PRECISION) / 255;
+        for (i, expected) in [
+            (
+                0,
+                "115792089237316195423570985008687907853269984665640564039457584007913129639936",
+            ),
+            (
+                1,
+                "113543770489016942962237377411819033281128847358465443152965692667674515268459",
+            ),
+            (
+                51,
+                "42597529080697662913911602080197270017224605406643086589378214572018159359656",
+            ),
+            (
+                102,
+                "15670755193491319402603874616100339746216923922257041729716527735529769853650",
+            ),
+            (
+                153,
+                "5764948663316064068243885471976803898564123312018636117461200936317441104963",
+            ),
+            (
+                204,
+                "2120806092642762118940557002260420725877403403643132375546681834433562628493",
+            ),
+            (
+                254,
+                "795649984289979771219745127738174910086802387263030261900019674061458963150",
+            ),
+            (
+                255,
+                "780200960193843203865524721501305631184795994974723658032277935470179306730",
+            ),
+        ] {
+            assert_eq!(expneg(&(&step * i)), q256(expected), "point {i}");
+        }
+    }
+
+    #[test]
+    fn lotus_poisson_vectors() {
+        // lambda is `base * 2^-shift`, the values are the inverse CDF at k = 0, 1 and 2
+        for (base, shift, expected) in [
+            (
+                10u64,
+                10,
+                [
+                    "1125278653883954157340515998824199281259686237023612910954531537010133365568",
+                    "5485581780123676224984074899749002236148166431650497590243915223971292577",
+                    "17842170241691453912141677461647358103546946338181118738604570718548080",
+                ],
+            ),
+            (
+                209714,
+                20,
+                [
+                    "20989436322611254574979389012727393136091537312055593688180077279147576363096",
+                    "2029014232696520721523332453453803636238058967796600089005757967894658844577",
+                    "132982872945592560215194824292206599698851338836977427578620974467337246296",
+                ],
+            ),
+            (
+                1036915,
+                20,
+                [
+                    "72718197862321603787957847055188249408647877796280116987586082825356498974798",
+                    "30123322455004902866096392147720313525979066051167804713805891738863284666878",
+                    "9062729215708086547397523150443475826195010851218953471088727298493148732956",
+                ],
+            ),
+            (
+                1706,
+                10,
+                [
+                    "93907545465879218275260347624273708225148723352890415890955745471328115138024",
+                    "57447553596668785643406883388130520172829512611140657354486862128150346836988",
+                    "27076095525930017054568011324233899656590951319429188573619716140132147265911",
+                ],
+            ),
+            (
+                2,
+                0,
+                [
+                    "100121334043824876020967110392587568107053060743383522309741056272383359786578",
+                    "68779823656842237215759361160386888614619212898869438850308000801323820079862",
+                    "37438313269859598410551611928186209122185365054355355390874945330264280373146",
+                ],
+            ),
+            (
+                5242879,
+                20,
+                [
+                    "115011887533064380050215202002871794783671664388541274939039184893270600703151",
+                    "111110879755863630144777547269452207577572562543679248972859798819416242535921",
+                    "101358362173007217989878395557465593373392010546833825352856759707561945139525",
+                ],
+            ),
+            (
+                5,
+                0,
+                [
+                    "115011888277122352219705460287186602222085188670665840381425306072442950421456",
+                    "111110883476153136200377836679680074066161208695792222091263916395092054329056",
+                    "101358371473730096152058777660913753676351258758608176365860442201714814098056",
+                ],
+            ),
+        ] {
+            let mut poisson = Poisson::new(BigInt::from(base) << (PRECISION - shift));
+            for (k, expected) in expected.into_iter().enumerate() {
+                if k > 0 {
+                    poisson.next();
+                }
+                assert_eq!(poisson.icdf, q256(expected), "lambda {base}/2^{shift}, k {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn lotus_win_count_vectors() {
+        // 30% of the power, the proofs are the low five little endian bytes of 1000000 + i
+        let expected = [
+            1, 2, 2, 3, 3, 0, 6, 1, 0, 1, 2, 2, 0, 1, 0, 0, 3, 3, 2, 1, 2, 2, 3, 4, 1, 2, 3, 1, 0,
+            2, 3, 1, 0, 2, 1, 0, 1, 2, 1, 2, 2, 0, 3, 4, 0, 1, 1, 1, 0, 3, 1, 2, 0, 0, 2, 1, 0, 3,
+            0, 2, 0, 3, 1, 1,
+        ];
+        for (i, expected) in expected.into_iter().enumerate() {
+            let vrf_proof = (1_000_000 + i as u64).to_le_bytes();
+            assert_eq!(
+                win_count(&vrf_proof[..5], &BigInt::from(30), &BigInt::from(100), 5),
+                expected,
+                "proof {i}"
+            );
+        }
+    }
+}
@@ -0,0 +1,4 @@
+use crate::util::types::Result;
+
+/// `value` holds the number of blocks a miner may produce in the epoch.
+pub type ElectionWinCountResponse = Result<u64>;
@@ -3,7 +3,11 @@
 #![allow(clippy::upper_case_acronyms)]
 
 pub mod bls;
+pub mod delegated;
+pub mod election;
 pub mod proofs;
+pub mod randomness;
+pub mod secp;
 pub mod util;
 
 #[cfg(feature = "fvm")]
@@ -1,20 +1,27 @@
+use std::collections::BTreeMap;
 use std::fs;
 
+use anyhow::{ensure, Context};
 use blstrs::Scalar as Fr;
 use filecoin_proofs_api::seal;
 use filecoin_proofs_api::{
-    self as api, update, PieceInfo, SectorId, StorageProofsError, UnpaddedByteIndex,
-    UnpaddedBytesAmount,
+    self as api, update, PieceInfo, PoStType, SectorId, SnarkProof, StorageProofsError,
+    UnpaddedByteIndex, UnpaddedBytesAmount,
 };
 use rayon::prelude::*;
 use safer_ffi::prelude::*;
 
-use super::helpers::{to_private_replica_info_map, to_public_replica_info_map};
+use super::cache_check;
+use super::cancel;
+use super::helpers::{
+    catch_panic_response_classified, set_error, to_private_replica_info_map,
+    to_public_replica_info_map,
+};
+use super::progress::{self, SealProgressCallback, Stage};
 use super::types::*;
+use super::vanilla;
 use crate::destructor;
-use crate::util::types::{
-    as_path_buf, catch_panic_response, catch_panic_response_raw, FCPResponseStatus,
-};
+use crate::util::types::{as_path_buf, catch_panic_response_raw, FCPResponseStatus};
 
 #[ffi_export]
 fn alloc_boxed_slice(size: usize) -> c_slice::Box<u8> {
@@ -38,7 +45,7 @@ unsafe fn write_with_alignment(
     dst_fd: libc::c_int,
     existing_piece_sizes: c_slice::Ref<'_, u64>,
 ) -> repr_c::Box<WriteWithAlignmentResponse> {
-    catch_panic_response("write_with_alignment", || {
+    catch_panic_response_classified("write_with_alignment", || {
         let piece_sizes: Vec<UnpaddedBytesAmount> = existing_piece_sizes
             .iter()
             .copied()
@@ -71,7 +78,7 @@ unsafe fn write_without_alignment(
     src_size: u64,
     dst_fd: libc::c_int,
 ) -> repr_c::Box<WriteWithoutAlignmentResponse> {
-    catch_panic_response("write_without_alignment", || {
+    catch_panic_response_classified("write_without_alignment", || {
         let (info, written) = seal::write_and_preprocess(
             registered_proof.into(),
             FileDescriptorRef::new(src_fd),
@@ -92,7 +99,7 @@ fn fauxrep(
     cache_dir_path: c_slice::Ref<'_, u8>,
     sealed_sector_path: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<FauxRepResponse> {
-    catch_panic_response("fauxrep", || {
+    catch_panic_response_classified("fauxrep", || {
         let res = seal::fauxrep(
             registered_proof.into(),
             as_path_buf(&cache_dir_path)?,
@@ -108,7 +115,7 @@ fn fauxrep2(
     cache_dir_path: c_slice::Ref<'_, u8>,
     existing_p_aux_path: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<FauxRepResponse> {
-    catch_panic_response("fauxrep2", || {
+    catch_panic_response_classified("fauxrep2", || {
         let result = seal::fauxrep2(
             registered_proof.into(),
             as_path_buf(&cache_dir_path)?,
@@ -131,23 +138,84 @@ fn seal_pre_commit_phase1(
     ticket: &[u8; 32],
     pieces: c_slice::Ref<'_, PublicPieceInfo>,
 ) -> repr_c::Box<SealPreCommitPhase1Response> {
-    catch_panic_response("seal_pre_commit_phase1", || {
-        let public_pieces: Vec<PieceInfo> = pieces.iter().map(Into::into).collect();
+    catch_panic_response_classified("seal_pre_commit_phase1", || {
+        pre_commit_phase1(
+            registered_proof,
+            cache_dir_path,
+            staged_sector_path,
+            sealed_sector_path,
+            sector_id,
+            prover_id,
+            ticket,
+            pieces,
+            None,
+        )
+    })
+}
 
-        let result = seal::seal_pre_commit_phase1(
+/// Same as [`seal_pre_commit_phase1`], reporting each label layer that is done to `progress`.
+///
+/// The callback is called from a separate thread, but never concurrently, and not after this
+/// function returned.
+#[ffi_export]
+#[allow(clippy::too_many_arguments)]
+fn seal_pre_commit_phase1_with_progress(
+    registered_proof: RegisteredSealProof,
+    cache_dir_path: c_slice::Ref<'_, u8>,
+    staged_sector_path: c_slice::Ref<'_, u8>,
+    sealed_sector_path: c_slice::Ref<'_, u8>,
+    sector_id: u64,
+    prover_id: &[u8; 32],
+    ticket: &[u8; 32],
+    pieces: c_slice::Ref<'_, PublicPieceInfo>,
+    progress: SealProgressCallback<'_>,
+) -> repr_c::Box<SealPreCommitPhase1Response> {
+    catch_panic_response_classified("seal_pre_commit_phase1_with_progress", || {
+        pre_commit_phase1(
+            registered_proof,
+            cache_dir_path,
+            staged_sector_path,
+            sealed_sector_path,
+            sector_id,
+            prover_id,
+            ticket,
+            pieces,
+            Some(progress),
+        )
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pre_commit_phase1(
+    registered_proof: RegisteredSealProof,
+    cache_dir_path: c_slice::Ref<'_, u8>,
+    staged_sector_path: c_slice::Ref<'_, u8>,
+    sealed_sector_path: c_slice::Ref<'_, u8>,
+    sector_id: u64,
+    prover_id: &[u8; 32],
+    ticket: &[u8; 32],
+    pieces: c_slice::Ref<'_, PublicPieceInfo>,
+    progress: Option<SealProgressCallback<'_>>,
+) -> anyhow::Result<c_slice::Box<u8>> {
+    let public_pieces: Vec<PieceInfo> = pieces.iter().map(Into::into).collect();
+    let cache_dir_path = as_path_buf(&cache_dir_path)?;
+
+    let stage = Stage::layers(registered_proof.into(), &cache_dir_path);
+    let result = progress::track(progress, stage, || {
+        seal::seal_pre_commit_phase1(
             registered_proof.into(),
-            as_path_buf(&cache_dir_path)?,
+            cache_dir_path.clone(),
             as_path_buf(&staged_sector_path)?,
             as_path_buf(&sealed_sector_path)?,
             *prover_id,
             SectorId::from(sector_id),
             *ticket,
             &public_pieces,
-        )?;
-        let result = serde_json::to_vec(&result)?;
+        )
+    })?;
+    let result = serde_json::to_vec(&result)?;
 
-        Ok(result.into_boxed_slice().into())
-    })
+    Ok(result.into_boxed_slice().into())
 }
 
 /// Runs the SDR process the same way as it would during PreCommit Phase 1.
@@ -160,14 +228,42 @@ fn generate_sdr(
     output_dir: c_slice::Ref<'_, u8>,
     replica_id: &[u8; 32],
 ) -> repr_c::Box<GenerateSdrResponse> {
-    catch_panic_response("generate_sdr", || {
+    catch_panic_response_classified("generate_sdr", || {
+        sdr(registered_proof, output_dir, replica_id, None)
+    })
+}
+
+/// Same as [`generate_sdr`], reporting each label layer that is done to `progress`.
+///
+/// The callback is called from a separate thread, but never concurrently, and not after this
+/// function returned.
+#[ffi_export]
+fn generate_sdr_with_progress(
+    registered_proof: RegisteredSealProof,
+    output_dir: c_slice::Ref<'_, u8>,
+    replica_id: &[u8; 32],
+    progress: SealProgressCallback<'_>,
+) -> repr_c::Box<GenerateSdrResponse> {
+    catch_panic_response_classified("generate_sdr_with_progress", || {
+        sdr(registered_proof, output_dir, replica_id, Some(progress))
+    })
+}
+
+fn sdr(
+    registered_proof: RegisteredSealProof,
+    output_dir: c_slice::Ref<'_, u8>,
+    replica_id: &[u8; 32],
+    progress: Option<SealProgressCallback<'_>>,
+) -> anyhow::Result<()> {
+    let output_dir = as_path_buf(&output_dir)?;
+
+    let stage = Stage::layers(registered_proof.into(), &output_dir);
+    progress::track(progress, stage, || {
         seal::sdr(
             registered_proof.into(),
-            as_path_buf(&output_dir)?,
+            output_dir.clone(),
             (*replica_id).into(),
-        )?;
-
-        Ok(())
+        )
     })
 }
 
@@ -178,20 +274,61 @@ fn seal_pre_commit_phase2(
     cache_dir_path: c_slice::Ref<'_, u8>,
     sealed_sector_path: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<SealPreCommitPhase2Response> {
-    catch_panic_response("seal_pre_commit_phase2", || {
-        let phase_1_output = serde_json::from_slice(&seal_pre_commit_phase1_output)?;
+    catch_panic_response_classified("seal_pre_commit_phase2", || {
+        pre_commit_phase2(
+            seal_pre_commit_phase1_output,
+            cache_dir_path,
+            sealed_sector_path,
+            None,
+        )
+    })
+}
 
-        let output = seal::seal_pre_commit_phase2(
+/// Same as [`seal_pre_commit_phase2`], reporting the percentage of the trees that are built to
+/// `progress`.
+///
+/// The callback is called from a separate thread, but never concurrently, and not after this
+/// function returned.
+#[ffi_export]
+fn seal_pre_commit_phase2_with_progress(
+    seal_pre_commit_phase1_output: c_slice::Ref<'_, u8>,
+    cache_dir_path: c_slice::Ref<'_, u8>,
+    sealed_sector_path: c_slice::Ref<'_, u8>,
+    progress: SealProgressCallback<'_>,
+) -> repr_c::Box<SealPreCommitPhase2Response> {
+    catch_panic_response_classified("seal_pre_commit_phase2_with_progress", || {
+        pre_commit_phase2(
+            seal_pre_commit_phase1_output,
+            cache_dir_path,
+            sealed_sector_path,
+            Some(progress),
+        )
+    })
+}
+
+fn pre_commit_phase2(
+    seal_pre_commit_phase1_output: c_slice::Ref<'_, u8>,
+    cache_dir_path: c_slice::Ref<'_, u8>,
+    sealed_sector_path: c_slice::Ref<'_, u8>,
+    progress: Option<SealProgressCallback<'_>>,
+) -> anyhow::Result<SealPreCommitPhase2> {
+    let phase_1_output: seal::SealPreCommitPhase1Output =
+        serde_json::from_slice(&seal_pre_commit_phase1_output)?;
+    let cache_dir_path = as_path_buf(&cache_dir_path)?;
+
+    let stage = Stage::trees(phase_1_output.registered_proof, &cache_dir_path);
+    let output = progress::track(progress, stage, || {
+        seal::seal_pre_commit_phase2(
             phase_1_output,
-            as_path_buf(&cache_dir_path)?,
+            cache_dir_path.clone(),
             as_path_buf(&sealed_sector_path)?,
-        )?;
+        )
+    })?;
 
-        Ok(SealPreCommitPhase2 {
-            comm_r: output.comm_r,
-            comm_d: output.comm_d,
-            registered_proof: output.registered_proof.into(),
-        })
+    Ok(SealPreCommitPhase2 {
+        comm_r: output.comm_r,
+        comm_d: output.comm_d,
+        registered_proof: output.registered_proof.into(),
     })
 }
 
@@ -205,7 +342,7 @@ fn generate_tree_r_last(
     replica_path: c_slice::Ref<'_, u8>,
     output_dir: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<GenerateTreeRLastResponse> {
-    catch_panic_response("generate_tree_r_last", || {
+    catch_panic_response_classified("generate_tree_r_last", || {
         let comm_r_last = seal::generate_tree_r_last(
             registered_proof.into(),
             as_path_buf(&replica_path)?,
@@ -230,7 +367,7 @@ fn generate_tree_c(
     input_dir: c_slice::Ref<'_, u8>,
     output_dir: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<GenerateTreeCResponse> {
-    catch_panic_response("generate_tree_c", || {
+    catch_panic_response_classified("generate_tree_c", || {
         let comm_c = seal::generate_tree_c(
             registered_proof.into(),
             as_path_buf(&input_dir)?,
@@ -241,6 +378,27 @@ fn generate_tree_c(
     })
 }
 
+/// Checks whether the cache directory of a sealed sector is usable to generate PoSts.
+///
+/// The report holds the outcome of each check, `p_aux`, `t_aux` and the TreeRLast files are read
+/// but no proofs are generated. An error is only returned for invalid arguments.
+#[ffi_export]
+fn check_sector_cache(
+    registered_proof: RegisteredSealProof,
+    cache_dir_path: c_slice::Ref<'_, u8>,
+    replica_path: c_slice::Ref<'_, u8>,
+    comm_r: &[u8; 32],
+) -> repr_c::Box<CheckSectorCacheResponse> {
+    catch_panic_response_classified("check_sector_cache", || {
+        Ok(cache_check::check_sector_cache(
+            registered_proof.into(),
+            &as_path_buf(&cache_dir_path)?,
+            &as_path_buf(&replica_path)?,
+            comm_r,
+        ))
+    })
+}
+
 /// TODO: document
 #[ffi_export]
 fn seal_commit_phase1(
@@ -255,7 +413,7 @@ fn seal_commit_phase1(
     seed: &[u8; 32],
     pieces: c_slice::Ref<'_, PublicPieceInfo>,
 ) -> repr_c::Box<SealCommitPhase1Response> {
-    catch_panic_response("seal_commit_phase1", || {
+    catch_panic_response_classified("seal_commit_phase1", || {
         let spcp2o = seal::SealPreCommitPhase2Output {
             registered_proof: registered_proof.into(),
             comm_r: *comm_r,
@@ -286,14 +444,77 @@ fn seal_commit_phase2(
     sector_id: u64,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<SealCommitPhase2Response> {
-    catch_panic_response("seal_commit_phase2", || {
-        let scp1o = serde_json::from_slice(&seal_commit_phase1_output)?;
-        let result = seal::seal_commit_phase2(scp1o, *prover_id, SectorId::from(sector_id))?;
+    catch_panic_response_classified("seal_commit_phase2", || {
+        commit_phase2(seal_commit_phase1_output, sector_id, prover_id, None, None)
+    })
+}
 
-        Ok(result.proof.into_boxed_slice().into())
+/// Same as [`seal_commit_phase2`], reporting the partition proofs that are done to `progress`.
+///
+/// All partitions are proven in a single batch that reports nothing while it runs, so the only
+/// events are `0` of the partitions when proving starts and all of them once the proof is done.
+/// The callback is not called after this function returned.
+#[ffi_export]
+fn seal_commit_phase2_with_progress(
+    seal_commit_phase1_output: c_slice::Ref<'_, u8>,
+    sector_id: u64,
+    prover_id: &[u8; 32],
+    progress: SealProgressCallback<'_>,
+) -> repr_c::Box<SealCommitPhase2Response> {
+    catch_panic_response_classified("seal_commit_phase2_with_progress", || {
+        commit_phase2(
+            seal_commit_phase1_output,
+            sector_id,
+            prover_id,
+            Some(progress),
+            None,
+        )
+    })
+}
+
+/// Same as [`seal_commit_phase2`], returning a response with the `Cancelled` status once `token`
+/// is cancelled.
+///
+/// The token is checked before proving starts and once the proof is done. All partitions are
+/// proven in a single SNARK that can't be interrupted, so a cancellation while it runs only takes
+/// effect once it is done, and the proof is discarded.
+#[ffi_export]
+fn seal_commit_phase2_cancellable(
+    seal_commit_phase1_output: c_slice::Ref<'_, u8>,
+    sector_id: u64,
+    prover_id: &[u8; 32],
+    token: &CancellationToken,
+) -> repr_c::Box<SealCommitPhase2Response> {
+    catch_panic_response_classified("seal_commit_phase2_cancellable", || {
+        commit_phase2(
+            seal_commit_phase1_output,
+            sector_id,
+            prover_id,
+            None,
+            Some(token),
+        )
     })
 }
 
+fn commit_phase2(
+    seal_commit_phase1_output: c_slice::Ref<'_, u8>,
+    sector_id: u64,
+    prover_id: &[u8; 32],
+    progress: Option<SealProgressCallback<'_>>,
+    token: Option<&CancellationToken>,
+) -> anyhow::Result<c_slice::Box<u8>> {
+    cancel::check(token)?;
+    let scp1o: seal::SealCommitPhase1Output = serde_json::from_slice(&seal_commit_phase1_output)?;
+
+    let stage = Stage::partitions(scp1o.registered_proof);
+    let result = progress::track(progress, stage, || {
+        seal::seal_commit_phase2(scp1o, *prover_id, SectorId::from(sector_id))
+    })?;
+    cancel::check(token)?;
+
+    Ok(result.proof.into_boxed_slice().into())
+}
+
 /// This function generates a variant of the circuit proof for the second phase of the sealing
 /// process. It takes as input the output from the first phase of the sealing process
 /// [`seal_commit_phase1`] and a sector ID.
@@ -316,7 +537,7 @@ fn seal_commit_phase2_circuit_proofs(
     seal_commit_phase1_output: c_slice::Ref<'_, u8>,
     sector_id: u64,
 ) -> repr_c::Box<SealCommitPhase2Response> {
-    catch_panic_response("seal_commit_phase2_circuit_proofs", || {
+    catch_panic_response_classified("seal_commit_phase2_circuit_proofs", || {
         let scp1o = serde_json::from_slice(&seal_commit_phase1_output)?;
         let result = seal::seal_commit_phase2_circuit_proofs(scp1o, SectorId::from(sector_id))?;
 
@@ -337,7 +558,7 @@ fn generate_synth_proofs(
     ticket: &[u8; 32],
     pieces: c_slice::Ref<'_, PublicPieceInfo>,
 ) -> repr_c::Box<GenerateSynthProofsResponse> {
-    catch_panic_response("generate_synth_proofs", || {
+    catch_panic_response_classified("generate_synth_proofs", || {
         let spcp2o = seal::SealPreCommitPhase2Output {
             registered_proof: registered_proof.into(),
             comm_r: *comm_r,
@@ -366,7 +587,7 @@ fn aggregate_seal_proofs(
     seeds: c_slice::Ref<'_, [u8; 32]>,
     seal_commit_responses: c_slice::Ref<'_, c_slice::Box<u8>>,
 ) -> repr_c::Box<AggregateProof> {
-    catch_panic_response("aggregate_seal_proofs", || {
+    catch_panic_response_classified("aggregate_seal_proofs", || {
         let outputs: Vec<seal::SealCommitPhase2Output> = seal_commit_responses
             .iter()
             .map(|p| seal::SealCommitPhase2Output { proof: p.to_vec() })
@@ -410,7 +631,7 @@ fn verify_aggregate_seal_proof(
     proof: c_slice::Ref<'_, u8>,
     commit_inputs: c_slice::Ref<'_, AggregationInputs>,
 ) -> repr_c::Box<VerifyAggregateSealProofResponse> {
-    catch_panic_response("verify_aggregate_seal_proof", || {
+    catch_panic_response_classified("verify_aggregate_seal_proof", || {
         let inputs: Vec<Vec<Fr>> = commit_inputs
             .par_iter()
             .map(|input| convert_aggregation_inputs(registered_proof, prover_id, input))
@@ -451,7 +672,7 @@ unsafe fn unseal_range(
     unpadded_byte_index: u64,
     unpadded_bytes_amount: u64,
 ) -> repr_c::Box<UnsealRangeResponse> {
-    catch_panic_response("unseal_range", || {
+    catch_panic_response_classified("unseal_range", || {
         use filepath::FilePath;
         use std::os::unix::io::{FromRawFd, IntoRawFd};
 
@@ -491,7 +712,7 @@ fn verify_seal(
     sector_id: u64,
     proof: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<super::types::VerifySealResponse> {
-    catch_panic_response("verify_seal", || {
+    catch_panic_response_classified("verify_seal", || {
         let proof_bytes: Vec<u8> = proof.to_vec();
 
         let result = seal::verify_seal(
@@ -517,7 +738,7 @@ fn generate_winning_post_sector_challenge(
     sector_set_len: u64,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<GenerateWinningPoStSectorChallenge> {
-    catch_panic_response("generate_winning_post_sector_challenge", || {
+    catch_panic_response_classified("generate_winning_post_sector_challenge", || {
         let result = filecoin_proofs_api::post::generate_winning_post_sector_challenge(
             registered_proof.into(),
             randomness,
@@ -541,7 +762,7 @@ fn generate_fallback_sector_challenges(
     sector_ids: c_slice::Ref<'_, u64>,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<GenerateFallbackSectorChallengesResponse> {
-    catch_panic_response("generate_fallback_sector_challenges", || {
+    catch_panic_response_classified("generate_fallback_sector_challenges", || {
         let pub_sectors: Vec<SectorId> = sector_ids.iter().copied().map(Into::into).collect();
 
         let output = filecoin_proofs_api::post::generate_fallback_sector_challenges(
@@ -571,7 +792,7 @@ fn generate_single_vanilla_proof(
     replica: PrivateReplicaInfo,
     challenges: c_slice::Ref<'_, u64>,
 ) -> repr_c::Box<GenerateSingleVanillaProofResponse> {
-    catch_panic_response("generate_single_vanilla_proof", || {
+    catch_panic_response_classified("generate_single_vanilla_proof", || {
         let sector_id = SectorId::from(replica.sector_id);
         let cache_dir_path = as_path_buf(&replica.cache_dir_path)?;
         let replica_path = as_path_buf(&replica.replica_path)?;
@@ -593,6 +814,77 @@ fn generate_single_vanilla_proof(
     })
 }
 
+/// Verifies a vanilla proof produced by [`generate_single_vanilla_proof`]
+///
+/// This checks the proof before it is passed to [`generate_winning_post_with_vanilla`] or
+/// [`generate_window_post_with_vanilla`], where an invalid proof makes proving its partition fail.
+/// A proof that doesn't prove `challenges` for the sector, or can't be deserialized, is invalid.
+///
+/// # Arguments
+///
+/// * `registered_proof` - the PoSt proof type the vanilla proof was generated for
+/// * `sector_id`        - the proven sector
+/// * `comm_r`           - replica commitment of the sector
+/// * `challenges`       - challenges of the sector, from [`generate_fallback_sector_challenges`]
+/// * `vanilla_proof`    - the vanilla proof
+#[ffi_export]
+fn verify_single_vanilla_proof(
+    registered_proof: RegisteredPoStProof,
+    sector_id: u64,
+    comm_r: &[u8; 32],
+    challenges: c_slice::Ref<'_, u64>,
+    vanilla_proof: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<VerifySingleVanillaProofResponse> {
+    catch_panic_response_classified("verify_single_vanilla_proof", || {
+        vanilla::verify_single_vanilla_proof(
+            registered_proof.into(),
+            sector_id.into(),
+            comm_r,
+            &challenges,
+            &vanilla_proof,
+        )
+    })
+}
+
+/// Same as [`verify_single_vanilla_proof`], verifying the vanilla proofs of many sectors in
+/// parallel. `challenges` and `vanilla_proofs` are given in the order of `replicas`.
+#[ffi_export]
+fn verify_single_vanilla_proofs(
+    replicas: c_slice::Ref<'_, PublicReplicaInfo>,
+    challenges: c_slice::Ref<'_, c_slice::Box<u64>>,
+    vanilla_proofs: c_slice::Ref<'_, VanillaProof>,
+) -> repr_c::Box<VerifySingleVanillaProofsResponse> {
+    catch_panic_response_classified("verify_single_vanilla_proofs", || {
+        if challenges.len() != replicas.len() || vanilla_proofs.len() != replicas.len() {
+            return Err(ProofsError::InvalidInputLength).with_context(|| {
+                format!(
+                    "got {} replicas, {} challenges and {} vanilla proofs",
+                    replicas.len(),
+                    challenges.len(),
+                    vanilla_proofs.len()
+                )
+            });
+        }
+
+        let valid = replicas
+            .par_iter()
+            .zip(challenges.par_iter())
+            .zip(vanilla_proofs.par_iter())
+            .map(|((replica, challenges), vanilla_proof)| {
+                vanilla::verify_single_vanilla_proof(
+                    replica.registered_proof.into(),
+                    replica.sector_id.into(),
+                    &replica.comm_r,
+                    challenges,
+                    vanilla_proof,
+                )
+            })
+            .collect::<anyhow::Result<Vec<bool>>>()?;
+
+        Ok(valid.into_boxed_slice().into())
+    })
+}
+
 /// TODO: document
 #[ffi_export]
 fn generate_winning_post_with_vanilla(
@@ -601,7 +893,7 @@ fn generate_winning_post_with_vanilla(
     prover_id: &[u8; 32],
     vanilla_proofs: c_slice::Ref<'_, VanillaProof>,
 ) -> repr_c::Box<GenerateWinningPoStResponse> {
-    catch_panic_response("generate_winning_post_with_vanilla", || {
+    catch_panic_response_classified("generate_winning_post_with_vanilla", || {
         let vanilla_proofs: Vec<_> = vanilla_proofs
             .iter()
             .map(|vanilla_proof| vanilla_proof.to_vec())
@@ -633,7 +925,7 @@ fn generate_winning_post(
     replicas: c_slice::Ref<'_, PrivateReplicaInfo>,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<GenerateWinningPoStResponse> {
-    catch_panic_response("generate_winning_post", || {
+    catch_panic_response_classified("generate_winning_post", || {
         let replicas = to_private_replica_info_map(replicas)?;
         let result =
             filecoin_proofs_api::post::generate_winning_post(randomness, &replicas, *prover_id)?;
@@ -658,7 +950,7 @@ fn verify_winning_post(
     proofs: c_slice::Ref<'_, PoStProof>,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<VerifyWinningPoStResponse> {
-    catch_panic_response("verify_winning_post", || {
+    catch_panic_response_classified("verify_winning_post", || {
         let replicas = to_public_replica_info_map(replicas);
         let proofs: Vec<u8> = proofs
             .iter()
@@ -695,40 +987,7 @@ fn generate_window_post_with_vanilla(
             &vanilla_proofs,
         );
 
-        let mut response = GenerateWindowPoStResponse::default();
-
-        match result {
-            Ok(output) => {
-                let mapped: Vec<PoStProof> = output
-                    .into_iter()
-                    .map(|(t, proof)| PoStProof {
-                        registered_proof: t.into(),
-                        proof: proof.into_boxed_slice().into(),
-                    })
-                    .collect();
-
-                response.status_code = FCPResponseStatus::NoError;
-                response.value.proofs = mapped.into_boxed_slice().into();
-            }
-            Err(err) => {
-                // If there were faulty sectors, add them to the response
-                if let Some(StorageProofsError::FaultySectors(sectors)) =
-                    err.downcast_ref::<StorageProofsError>()
-                {
-                    let sectors_u64 = sectors
-                        .iter()
-                        .map(|sector| u64::from(*sector))
-                        .collect::<Vec<u64>>();
-
-                    response.value.faulty_sectors = sectors_u64.into_boxed_slice().into()
-                }
-
-                response.status_code = FCPResponseStatus::UnclassifiedError;
-                response.error_msg = err.to_string().into_bytes().into_boxed_slice().into();
-            }
-        }
-
-        response
+        window_post_response(result)
     })
 }
 
@@ -744,40 +1003,131 @@ fn generate_window_post(
             filecoin_proofs_api::post::generate_window_post(randomness, &replicas, *prover_id)
         });
 
-        let mut response = GenerateWindowPoStResponse::default();
-        match result {
-            Ok(output) => {
-                let mapped: Vec<PoStProof> = output
-                    .into_iter()
-                    .map(|(t, proof)| PoStProof {
-                        registered_proof: t.into(),
-                        proof: proof.into_boxed_slice().into(),
-                    })
-                    .collect();
+        window_post_response(result)
+    })
+}
 
-                response.status_code = FCPResponseStatus::NoError;
-                response.value.proofs = mapped.into_boxed_slice().into();
-            }
-            Err(err) => {
-                // If there were faulty sectors, add them to the response
-                if let Some(StorageProofsError::FaultySectors(sectors)) =
-                    err.downcast_ref::<StorageProofsError>()
-                {
-                    let sectors_u64 = sectors
-                        .iter()
-                        .map(|sector| u64::from(*sector))
-                        .collect::<Vec<u64>>();
+/// Same as [`generate_window_post`], returning a response with the `Cancelled` status once
+/// `token` is cancelled.
+///
+/// The vanilla proofs are generated sector by sector, which is where cancellation is checked.
+/// The partitions are proven at once afterwards, which can't be interrupted, so a cancellation
+/// during proving has no effect and the proofs are returned.
+#[ffi_export]
+fn generate_window_post_cancellable(
+    randomness: &[u8; 32],
+    replicas: c_slice::Ref<'_, PrivateReplicaInfo>,
+    prover_id: &[u8; 32],
+    token: &CancellationToken,
+) -> repr_c::Box<GenerateWindowPoStResponse> {
+    catch_panic_response_raw("generate_window_post_cancellable", || {
+        let result = replicas
+            .first()
+            .context("no replicas supplied")
+            .and_then(|replica| {
+                let registered_proof = replica.registered_proof.into();
+                let replicas = to_private_replica_info_map(replicas)?;
+                window_post(registered_proof, randomness, &replicas, prover_id, token)
+            });
+
+        window_post_response(result)
+    })
+}
 
-                    response.value.faulty_sectors = sectors_u64.into_boxed_slice().into();
-                }
+/// Generates a window PoSt from the vanilla proofs of the individual sectors, checking `token`
+/// in between.
+fn window_post(
+    registered_proof: api::RegisteredPoStProof,
+    randomness: &[u8; 32],
+    replicas: &BTreeMap<SectorId, api::PrivateReplicaInfo>,
+    prover_id: &[u8; 32],
+    token: &CancellationToken,
+) -> anyhow::Result<Vec<(api::RegisteredPoStProof, SnarkProof)>> {
+    ensure!(
+        registered_proof.typ() == PoStType::Window,
+        "invalid post type provided"
+    );
+
+    token.check()?;
+    let sector_ids: Vec<SectorId> = replicas.keys().copied().collect();
+    let challenges = filecoin_proofs_api::post::generate_fallback_sector_challenges(
+        registered_proof,
+        randomness,
+        &sector_ids,
+        *prover_id,
+    )?;
+
+    let vanilla_proofs: Vec<_> = replicas
+        .par_iter()
+        .map(|(sector_id, replica)| {
+            token.check()?;
+            filecoin_proofs_api::post::generate_single_vanilla_proof(
+                registered_proof,
+                *sector_id,
+                replica,
+                &challenges[sector_id],
+            )
+        })
+        .collect();
+    token.check()?;
+
+    // report all sectors that couldn't be proven, as the proofs library does
+    let faulty_sectors: Vec<SectorId> = sector_ids
+        .iter()
+        .zip(&vanilla_proofs)
+        .filter(|(_, proof)| proof.is_err())
+        .map(|(sector_id, _)| *sector_id)
+        .collect();
+    if !faulty_sectors.is_empty() {
+        return Err(StorageProofsError::FaultySectors(faulty_sectors).into());
+    }
+    let vanilla_proofs: Vec<_> = vanilla_proofs.into_iter().flatten().collect();
+
+    let proofs = filecoin_proofs_api::post::generate_window_post_with_vanilla(
+        registered_proof,
+        randomness,
+        *prover_id,
+        &vanilla_proofs,
+    )?;
+
+    Ok(proofs)
+}
+
+fn window_post_response(
+    result: anyhow::Result<Vec<(api::RegisteredPoStProof, SnarkProof)>>,
+) -> GenerateWindowPoStResponse {
+    let mut response = GenerateWindowPoStResponse::default();
+    match result {
+        Ok(output) => {
+            let mapped: Vec<PoStProof> = output
+                .into_iter()
+                .map(|(t, proof)| PoStProof {
+                    registered_proof: t.into(),
+                    proof: proof.into_boxed_slice().into(),
+                })
+                .collect();
+
+            response.status_code = FCPResponseStatus::NoError;
+            response.value.proofs = mapped.into_boxed_slice().into();
+        }
+        Err(err) => {
+            // If there were faulty sectors, add them to the response
+            if let Some(StorageProofsError::FaultySectors(sectors)) =
+                err.downcast_ref::<StorageProofsError>()
+            {
+                let sectors_u64 = sectors
+                    .iter()
+                    .map(|sector| u64::from(*sector))
+                    .collect::<Vec<u64>>();
 
-                response.status_code = FCPResponseStatus::UnclassifiedError;
-                response.error_msg = err.to_string().into_bytes().into_boxed_slice().into();
+                response.value.faulty_sectors = sectors_u64.into_boxed_slice().into();
             }
+
+            set_error(&mut response, &err);
         }
+    }
 
-        response
-    })
+    response
 }
 
 /// Verifies that a proof-of-spacetime is valid.
@@ -788,7 +1138,7 @@ fn verify_window_post(
     proofs: c_slice::Ref<'_, PoStProof>,
     prover_id: &[u8; 32],
 ) -> repr_c::Box<VerifyWindowPoStResponse> {
-    catch_panic_response("verify_window_post", || {
+    catch_panic_response_classified("verify_window_post", || {
         let replicas = to_public_replica_info_map(replicas);
         let proofs: Vec<(api::RegisteredPoStProof, &[u8])> = proofs
             .iter()
@@ -814,7 +1164,7 @@ fn merge_window_post_partition_proofs(
     registered_proof: RegisteredPoStProof,
     partition_proofs: c_slice::Ref<'_, c_slice::Box<u8>>,
 ) -> repr_c::Box<MergeWindowPoStPartitionProofsResponse> {
-    catch_panic_response("merge_window_post_partition_proofs", || {
+    catch_panic_response_classified("merge_window_post_partition_proofs", || {
         let partition_proofs = partition_proofs
             .iter()
             .map(|proof| api::PartitionSnarkProof(proof.to_vec()))
@@ -838,7 +1188,7 @@ fn get_num_partition_for_fallback_post(
     registered_proof: RegisteredPoStProof,
     num_sectors: libc::size_t,
 ) -> repr_c::Box<GetNumPartitionForFallbackPoStResponse> {
-    catch_panic_response("get_num_partition_for_fallback_post", || {
+    catch_panic_response_classified("get_num_partition_for_fallback_post", || {
         let result = filecoin_proofs_api::post::get_num_partition_for_fallback_post(
             registered_proof.into(),
             num_sectors,
@@ -895,8 +1245,7 @@ fn generate_single_window_post_with_vanilla(
                     response.value.faulty_sectors = sectors_u64.into_boxed_slice().into();
                 }
 
-                response.status_code = FCPResponseStatus::UnclassifiedError;
-                response.error_msg = err.to_string().into_bytes().into_boxed_slice().into();
+                set_error(&mut response, &err);
             }
         }
 
@@ -915,24 +1264,81 @@ fn empty_sector_update_encode_into(
     staged_data_path: c_slice::Ref<'_, u8>,
     pieces: c_slice::Ref<'_, PublicPieceInfo>,
 ) -> repr_c::Box<EmptySectorUpdateEncodeIntoResponse> {
-    catch_panic_response("empty_sector_update_encode_into", || {
-        let public_pieces = pieces.iter().map(Into::into).collect::<Vec<_>>();
+    catch_panic_response_classified("empty_sector_update_encode_into", || {
+        encode_into(
+            registered_proof,
+            new_replica_path,
+            new_cache_dir_path,
+            sector_key_path,
+            sector_key_cache_dir_path,
+            staged_data_path,
+            pieces,
+            None,
+        )
+    })
+}
 
-        let output = update::empty_sector_update_encode_into(
-            registered_proof.into(),
-            as_path_buf(&new_replica_path)?,
-            as_path_buf(&new_cache_dir_path)?,
-            as_path_buf(&sector_key_path)?,
-            as_path_buf(&sector_key_cache_dir_path)?,
-            as_path_buf(&staged_data_path)?,
-            &public_pieces,
-        )?;
+/// Same as [`empty_sector_update_encode_into`], returning a response with the `Cancelled` status
+/// once `token` is cancelled.
+///
+/// The token is checked before the encoding starts and once it is done. The encoding can't be
+/// interrupted, so a cancellation while it runs only takes effect once it is done. The new
+/// replica and cache directory are complete then, and can be removed as usual.
+#[ffi_export]
+#[allow(clippy::too_many_arguments)]
+fn empty_sector_update_encode_into_cancellable(
+    registered_proof: RegisteredUpdateProof,
+    new_replica_path: c_slice::Ref<'_, u8>,
+    new_cache_dir_path: c_slice::Ref<'_, u8>,
+    sector_key_path: c_slice::Ref<'_, u8>,
+    sector_key_cache_dir_path: c_slice::Ref<'_, u8>,
+    staged_data_path: c_slice::Ref<'_, u8>,
+    pieces: c_slice::Ref<'_, PublicPieceInfo>,
+    token: &CancellationToken,
+) -> repr_c::Box<EmptySectorUpdateEncodeIntoResponse> {
+    catch_panic_response_classified("empty_sector_update_encode_into_cancellable", || {
+        encode_into(
+            registered_proof,
+            new_replica_path,
+            new_cache_dir_path,
+            sector_key_path,
+            sector_key_cache_dir_path,
+            staged_data_path,
+            pieces,
+            Some(token),
+        )
+    })
+}
 
-        Ok(EmptySectorUpdateEncodeInto {
-            comm_r_new: output.comm_r_new,
-            comm_r_last_new: output.comm_r_last_new,
-            comm_d_new: output.comm_d_new,
-        })
+#[allow(clippy::too_many_arguments)]
+fn encode_into(
+    registered_proof: RegisteredUpdateProof,
+    new_replica_path: c_slice::Ref<'_, u8>,
+    new_cache_dir_path: c_slice::Ref<'_, u8>,
+    sector_key_path: c_slice::Ref<'_, u8>,
+    sector_key_cache_dir_path: c_slice::Ref<'_, u8>,
+    staged_data_path: c_slice::Ref<'_, u8>,
+    pieces: c_slice::Ref<'_, PublicPieceInfo>,
+    token: Option<&CancellationToken>,
+) -> anyhow::Result<EmptySectorUpdateEncodeInto> {
+    let public_pieces = pieces.iter().map(Into::into).collect::<Vec<_>>();
+
+    cancel::check(token)?;
+    let output = update::empty_sector_update_encode_into(
+        registered_proof.into(),
+        as_path_buf(&new_replica_path)?,
+        as_path_buf(&new_cache_dir_path)?,
+        as_path_buf(&sector_key_path)?,
+        as_path_buf(&sector_key_cache_dir_path)?,
+        as_path_buf(&staged_data_path)?,
+        &public_pieces,
+    )?;
+    cancel::check(token)?;
+
+    Ok(EmptySectorUpdateEncodeInto {
+        comm_r_new: output.comm_r_new,
+        comm_r_last_new: output.comm_r_last_new,
+        comm_d_new: output.comm_d_new,
     })
 }
 
@@ -946,7 +1352,7 @@ fn empty_sector_update_decode_from(
     sector_key_cache_dir_path: c_slice::Ref<'_, u8>,
     comm_d_new: &[u8; 32],
 ) -> repr_c::Box<EmptySectorUpdateDecodeFromResponse> {
-    catch_panic_response("empty_sector_update_decode_from", || {
+    catch_panic_response_classified("empty_sector_update_decode_from", || {
         update::empty_sector_update_decode_from(
             registered_proof.into(),
             as_path_buf(&out_data_path)?,
@@ -991,7 +1397,7 @@ unsafe fn empty_sector_update_decode_from_range(
     nodes_offset: u64,
     num_nodes: u64,
 ) -> repr_c::Box<EmptySectorUpdateDecodeFromRangeResponse> {
-    catch_panic_response("empty_sector_update_decode_from_range", || {
+    catch_panic_response_classified("empty_sector_update_decode_from_range", || {
         update::empty_sector_update_decode_from_range(
             registered_proof.into(),
             *comm_d,
@@ -1018,7 +1424,7 @@ fn empty_sector_update_remove_encoded_data(
     data_path: c_slice::Ref<'_, u8>,
     comm_d_new: &[u8; 32],
 ) -> repr_c::Box<EmptySectorUpdateRemoveEncodedDataResponse> {
-    catch_panic_response("empty_sector_update_remove_encoded_data", || {
+    catch_panic_response_classified("empty_sector_update_remove_encoded_data", || {
         update::empty_sector_update_remove_encoded_data(
             registered_proof.into(),
             as_path_buf(&sector_key_path)?,
@@ -1045,7 +1451,7 @@ fn generate_empty_sector_update_partition_proofs(
     replica_path: c_slice::Ref<'_, u8>,
     replica_cache_path: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<PartitionProofResponse> {
-    catch_panic_response("generate_empty_sector_update_partition_proofs", || {
+    catch_panic_response_classified("generate_empty_sector_update_partition_proofs", || {
         let output = update::generate_partition_proofs(
             registered_proof.into(),
             *comm_r_old,
@@ -1076,7 +1482,7 @@ fn verify_empty_sector_update_partition_proofs(
     comm_r_new: &[u8; 32],
     comm_d_new: &[u8; 32],
 ) -> repr_c::Box<VerifyPartitionProofResponse> {
-    catch_panic_response("verify_empty_sector_update_partition_proofs", || {
+    catch_panic_response_classified("verify_empty_sector_update_partition_proofs", || {
         let proofs: Vec<api::PartitionProofBytes> = proofs
             .iter()
             .map(|pp| api::PartitionProofBytes(pp.to_vec()))
@@ -1103,7 +1509,7 @@ fn generate_empty_sector_update_proof_with_vanilla(
     comm_r_new: &[u8; 32],
     comm_d_new: &[u8; 32],
 ) -> repr_c::Box<EmptySectorUpdateProofResponse> {
-    catch_panic_response("generate_empty_sector_update_proof_with_vanilla", || {
+    catch_panic_response_classified("generate_empty_sector_update_proof_with_vanilla", || {
         let partition_proofs: Vec<api::PartitionProofBytes> = vanilla_proofs
             .iter()
             .map(|partition_proof| api::PartitionProofBytes(partition_proof.to_vec()))
@@ -1133,7 +1539,7 @@ fn generate_empty_sector_update_proof(
     replica_path: c_slice::Ref<'_, u8>,
     replica_cache_path: c_slice::Ref<'_, u8>,
 ) -> repr_c::Box<EmptySectorUpdateProofResponse> {
-    catch_panic_response("generate_empty_sector_update_proof", || {
+    catch_panic_response_classified("generate_empty_sector_update_proof", || {
         let result = update::generate_empty_sector_update_proof(
             registered_proof.into(),
             *comm_r_old,
@@ -1158,7 +1564,7 @@ fn verify_empty_sector_update_proof(
     comm_r_new: &[u8; 32],
     comm_d_new: &[u8; 32],
 ) -> repr_c::Box<VerifyEmptySectorUpdateProofResponse> {
-    catch_panic_response("verify_empty_sector_update_proof", || {
+    catch_panic_response_classified("verify_empty_sector_update_proof", || {
         let proof_bytes: Vec<u8> = proof.to_vec();
 
         let result = update::verify_empty_sector_update_proof(
@@ -1181,7 +1587,7 @@ unsafe fn generate_piece_commitment(
     piece_fd_raw: libc::c_int,
     unpadded_piece_size: u64,
 ) -> repr_c::Box<GeneratePieceCommitmentResponse> {
-    catch_panic_response("generate_piece_commitment", || {
+    catch_panic_response_classified("generate_piece_commitment", || {
         use std::os::unix::io::{FromRawFd, IntoRawFd};
 
         let mut piece_file = fs::File::from_raw_fd(piece_fd_raw);
@@ -1211,7 +1617,7 @@ fn generate_data_commitment(
     registered_proof: RegisteredSealProof,
     pieces: c_slice::Ref<'_, PublicPieceInfo>,
 ) -> repr_c::Box<GenerateDataCommitmentResponse> {
-    catch_panic_response("generate_data_commitment", || {
+    catch_panic_response_classified("generate_data_commitment", || {
         let public_pieces: Vec<PieceInfo> = pieces.iter().map(Into::into).collect();
         let result = seal::compute_comm_d(registered_proof.into(), &public_pieces)?;
 
@@ -1221,14 +1627,14 @@ fn generate_data_commitment(
 
 #[ffi_export]
 fn clear_cache(cache_dir_path: c_slice::Ref<'_, u8>) -> repr_c::Box<ClearCacheResponse> {
-    catch_panic_response("clear_cache", || {
+    catch_panic_response_classified("clear_cache", || {
         seal::clear_cache(&as_path_buf(&cache_dir_path)?)
     })
 }
 
 #[ffi_export]
 fn clear_synthetic_proofs(cache_dir_path: c_slice::Ref<'_, u8>) -> repr_c::Box<ClearCacheResponse> {
-    catch_panic_response("clear_synthetic_proofs", || {
+    catch_panic_response_classified("clear_synthetic_proofs", || {
         seal::clear_synthetic_proofs(&as_path_buf(&cache_dir_path)?)
     })
 }
@@ -1391,6 +1797,10 @@ destructor!(
     SealPreCommitPhase1Response
 );
 destructor!(destroy_generate_sdr_response, GenerateSdrResponse);
+destructor!(
+    destroy_check_sector_cache_response,
+    CheckSectorCacheResponse
+);
 destructor!(
     destroy_seal_pre_commit_phase2_response,
     SealPreCommitPhase2Response
@@ -1440,6 +1850,14 @@ destructor!(
     destroy_generate_single_vanilla_proof_response,
     GenerateSingleVanillaProofResponse
 );
+destructor!(
+    destroy_verify_single_vanilla_proof_response,
+    VerifySingleVanillaProofResponse
+);
+destructor!(
+    destroy_verify_single_vanilla_proofs_response,
+    VerifySingleVanillaProofsResponse
+);
 destructor!(
     destroy_generate_single_window_post_with_vanilla_response,
     GenerateSingleWindowPoStWithVanillaResponse
@@ -1517,7 +1935,9 @@ pub mod tests {
     use log::info;
     use memmap2::MmapOptions;
     use rand::{thread_rng, Rng};
+    use safer_ffi::closure::RefDynFnMut1;
 
+    use crate::proofs::cancel::{cancellation_token_cancel, cancellation_token_new};
     use crate::util::types::as_bytes;
 
     use super::*;
@@ -1610,6 +2030,65 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_cancelled_before_start() {
+        let token = cancellation_token_new();
+        cancellation_token_cancel(&token);
+
+        let resp = seal_commit_phase2_cancellable(b"{}"[..].into(), 0, &[0; 32], &token);
+        assert_eq!(resp.status_code, FCPResponseStatus::Cancelled);
+        destroy_seal_commit_phase2_response(resp);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = as_bytes(dir.path());
+        let resp = empty_sector_update_encode_into_cancellable(
+            RegisteredUpdateProof::StackedDrg2KiBV1,
+            path.into(),
+            path.into(),
+            path.into(),
+            path.into(),
+            path.into(),
+            [][..].into(),
+            &token,
+        );
+        assert_eq!(resp.status_code, FCPResponseStatus::Cancelled);
+        destroy_empty_sector_update_encode_into_response(resp);
+        // nothing was written
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let replicas = [PrivateReplicaInfo {
+            registered_proof: RegisteredPoStProof::StackedDrgWindow2KiBV1_1,
+            cache_dir_path: path.to_vec().into_boxed_slice().into(),
+            comm_r: [1; 32],
+            replica_path: path.to_vec().into_boxed_slice().into(),
+            sector_id: 1,
+        }];
+        let resp =
+            generate_window_post_cancellable(&[1; 32], replicas[..].into(), &[0; 32], &token);
+        assert_eq!(resp.status_code, FCPResponseStatus::Cancelled);
+        assert!(resp.proofs.is_empty());
+        destroy_generate_window_post_response(resp);
+        drop(token);
+    }
+
+    #[test]
+    fn test_missing_file_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let path = as_bytes(&missing);
+        let resp = empty_sector_update_decode_from(
+            RegisteredUpdateProof::StackedDrg2KiBV1,
+            path.into(),
+            path.into(),
+            path.into(),
+            path.into(),
+            &[0; 32],
+        );
+        assert_eq!(resp.status_code, FCPResponseStatus::ReceiverError);
+        assert_eq!(resp.error_code, ProofsError::MissingFile as i32);
+        destroy_empty_sector_update_decode_from_response(resp);
+    }
+
     #[test]
     fn test_proof_types() {
         let seal_types = vec![
@@ -1842,6 +2321,31 @@ pub mod tests {
                 "pre-computed CommD and pre-commit CommD don't match"
             );
 
+            let resp_check = check_sector_cache(
+                registered_proof_seal,
+                cache_dir_path_ref.into(),
+                sealed_path_ref.into(),
+                &resp_b2.comm_r,
+            );
+
+            if resp_check.status_code != FCPResponseStatus::NoError {
+                let msg = str::from_utf8(&resp_check.error_msg).unwrap();
+                panic!("check_sector_cache failed: {:?}", msg);
+            }
+
+            let report = &resp_check.value;
+            for status in [
+                report.replica,
+                report.p_aux,
+                report.t_aux,
+                report.tree_r_last_files,
+                report.tree_r_last_root,
+                report.comm_r,
+            ] {
+                assert_eq!(status, SectorCacheCheckStatus::Ok, "{:?}", report);
+            }
+            destroy_check_sector_cache_response(resp_check);
+
             // If we're using SyntheticPoRep -- generate the synthetic proofs here and clear the layer data.
             if registered_proof_seal == RegisteredSealProof::StackedDrg2KiBV1_1_Feat_SyntheticPoRep
             {
@@ -1926,13 +2430,26 @@ pub mod tests {
 
             assert!(**resp_d, "proof was not valid");
 
-            let resp_c22 = seal_commit_phase2(resp_c1.as_ref(), sector_id, &prover_id);
+            let mut progress = Vec::new();
+            let resp_c22 = seal_commit_phase2_with_progress(
+                resp_c1.as_ref(),
+                sector_id,
+                &prover_id,
+                RefDynFnMut1::new(&mut |event| progress.push(event)),
+            );
 
             if resp_c22.status_code != FCPResponseStatus::NoError {
                 let msg = str::from_utf8(&resp_c22.error_msg).unwrap();
-                panic!("seal_commit_phase2 failed: {:?}", msg);
+                panic!("seal_commit_phase2_with_progress failed: {:?}", msg);
             }
 
+            let partitions = |done| SealProgress {
+                kind: SealProgressKind::PartitionProofDone,
+                done,
+                total: 1,
+            };
+            assert_eq!(progress, vec![partitions(0), partitions(1)]);
+
             let resp_d2 = verify_seal(
                 registered_proof_seal,
                 &resp_b2.comm_r,
@@ -2488,6 +3005,43 @@ pub mod tests {
                 panic!("verify_window_post rejected the provided proof as invalid");
             }
 
+            // the cancellable variant produces the same proofs until it is cancelled
+            let token = cancellation_token_new();
+            let resp_jc = generate_window_post_cancellable(
+                &randomness,
+                private_replicas[..].into(),
+                &prover_id,
+                &token,
+            );
+
+            if resp_jc.status_code != FCPResponseStatus::NoError {
+                let msg = str::from_utf8(&resp_jc.error_msg).unwrap();
+                panic!("generate_window_post_cancellable failed: {:?}", msg);
+            }
+
+            let resp_kc = verify_window_post(
+                &randomness,
+                public_replicas[..].into(),
+                resp_jc.proofs.as_ref(),
+                &prover_id,
+            );
+            assert!(**resp_kc, "cancellable window post is not valid");
+
+            cancellation_token_cancel(&token);
+            let resp_jc2 = generate_window_post_cancellable(
+                &randomness,
+                private_replicas[..].into(),
+                &prover_id,
+                &token,
+            );
+            assert_eq!(resp_jc2.status_code, FCPResponseStatus::Cancelled);
+            assert!(resp_jc2.proofs.is_empty());
+
+            destroy_generate_window_post_response(resp_jc);
+            destroy_generate_window_post_response(resp_jc2);
+            destroy_verify_window_post_response(resp_kc);
+            drop(token);
+
             // Generate a legacy WindowPoSt for later use.
             let legacy_registered_proof_window_post = RegisteredPoStProof::StackedDrgWindow2KiBV1;
             let legacy_private_replicas = [PrivateReplicaInfo {
@@ -2635,6 +3189,43 @@ pub mod tests {
                 destroy_generate_single_vanilla_proof_response(resp_vp);
             }
 
+            // Verify the vanilla proofs before proving with them.
+            let resp_vsvp = verify_single_vanilla_proof(
+                registered_proof_window_post,
+                sector_ids[0],
+                &resp_b2.comm_r,
+                sector_challenges[0].as_ref(),
+                vanilla_proofs[0].as_ref(),
+            );
+            if resp_vsvp.status_code != FCPResponseStatus::NoError {
+                let msg = str::from_utf8(&resp_vsvp.error_msg).unwrap();
+                panic!("verify_single_vanilla_proof failed: {:?}", msg);
+            }
+            assert!(**resp_vsvp, "vanilla proof rejected as invalid");
+            destroy_verify_single_vanilla_proof_response(resp_vsvp);
+
+            // A proof of another sector is invalid.
+            let replicas: Vec<_> = sector_ids
+                .iter()
+                .map(|&sector_id| PublicReplicaInfo {
+                    registered_proof: registered_proof_window_post,
+                    sector_id,
+                    comm_r: resp_b2.comm_r,
+                })
+                .collect();
+            let swapped = [vanilla_proofs[0].clone(), vanilla_proofs[0].clone()];
+            let resp_vsvps = verify_single_vanilla_proofs(
+                replicas[..].into(),
+                sector_challenges[..].into(),
+                swapped[..].into(),
+            );
+            if resp_vsvps.status_code != FCPResponseStatus::NoError {
+                let msg = str::from_utf8(&resp_vsvps.error_msg).unwrap();
+                panic!("verify_single_vanilla_proofs failed: {:?}", msg);
+            }
+            assert_eq!(&resp_vsvps.value[..], &[true, false]);
+            destroy_verify_single_vanilla_proofs_response(resp_vsvps);
+
             let resp_wpwv2 = generate_window_post_with_vanilla(
                 registered_proof_window_post,
                 &randomness,
@@ -2947,9 +3538,10 @@ pub mod tests {
 
             assert_eq!(
                 resp_j.status_code,
-                FCPResponseStatus::UnclassifiedError,
+                FCPResponseStatus::ReceiverError,
                 "generate_window_post should have failed"
             );
+            assert_eq!(resp_j.error_code, ProofsError::FaultySectors as i32);
 
             let faulty_sectors: &[u64] = &resp_j.faulty_sectors;
             assert_eq!(faulty_sectors, &[42], "sector 42 should be faulty");
@@ -0,0 +1,339 @@
+//! Inspection of the cache directory of a sealed sector, telling whether PoSts can be generated
+//! from it without proving anything.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use blstrs::Scalar as Fr;
+use filecoin_hashers::poseidon::{PoseidonDomain, PoseidonFunction};
+use filecoin_hashers::{Domain, HashFunction};
+use filecoin_proofs_api::{RegisteredSealProof, NODE_SIZE};
+use group::ff::PrimeField;
+use merkletree::hash::Algorithm;
+use merkletree::merkle::get_merkle_tree_cache_size;
+use merkletree::store::StoreConfig;
+use serde::{Deserialize, Serialize};
+
+use super::helpers::base_tree_count;
+use super::types::{SectorCacheCheck, SectorCacheCheckStatus as Status};
+
+/// File names of `filecoin_proofs::types::CacheKey`.
+const P_AUX_FILE: &str = "p_aux";
+const T_AUX_FILE: &str = "t_aux";
+
+/// Arities of the base trees, sub trees and top tree of TreeRLast.
+const BASE_TREE_ARITY: usize = 8;
+const SUB_TREE_ARITY: usize = 8;
+const TOP_TREE_ARITY: usize = 2;
+
+/// `TemporaryAux` of `storage_proofs_porep`, as stored in `t_aux`. It doesn't depend on the
+/// sector shape, as the tree types are phantom data which isn't serialized.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct TemporaryAux {
+    pub labels: Vec<StoreConfig>,
+    pub tree_d_config: StoreConfig,
+    pub tree_r_last_config: StoreConfig,
+    pub tree_c_config: StoreConfig,
+}
+
+/// Runs all checks of a [`SectorCacheCheck`]. Checks that depend on files which are missing or
+/// invalid are skipped.
+pub(crate) fn check_sector_cache(
+    registered_proof: RegisteredSealProof,
+    cache_dir: &Path,
+    replica_path: &Path,
+    comm_r: &[u8; 32],
+) -> SectorCacheCheck {
+    let sector_size = u64::from(registered_proof.sector_size());
+    let mut report = SectorCacheCheck {
+        replica: match fs::metadata(replica_path) {
+            Ok(metadata) if metadata.len() == sector_size => Status::Ok,
+            Ok(_) => Status::Invalid,
+            Err(err) => io_status(&err),
+        },
+        ..Default::default()
+    };
+
+    // bincode stores the two commitments, comm_c and comm_r_last, as they are
+    let p_aux = read_file(&cache_dir.join(P_AUX_FILE), |bytes| {
+        if bytes.len() != 2 * NODE_SIZE {
+            return None;
+        }
+        Some((
+            to_domain(&bytes[..NODE_SIZE])?,
+            to_domain(&bytes[NODE_SIZE..])?,
+        ))
+    });
+    report.p_aux = status(&p_aux);
+
+    let t_aux = read_file(&cache_dir.join(T_AUX_FILE), |bytes| {
+        bincode::deserialize::<TemporaryAux>(bytes).ok()
+    });
+    report.t_aux = status(&t_aux);
+
+    let roots = t_aux.and_then(|t_aux| {
+        tree_r_last_roots(
+            sector_size,
+            base_tree_count(registered_proof),
+            cache_dir,
+            &t_aux.tree_r_last_config,
+        )
+    });
+    report.tree_r_last_files = status(&roots);
+
+    if let Ok((comm_c, comm_r_last)) = p_aux {
+        if let Ok(roots) = roots {
+            report.tree_r_last_root = check(tree_root(roots) == comm_r_last);
+        }
+
+        let expected_comm_r = PoseidonFunction::hash2(&comm_c, &comm_r_last);
+        report.comm_r = check(expected_comm_r.into_bytes() == comm_r);
+    }
+
+    report
+}
+
+/// Reads the roots of the TreeRLast base trees, making sure their files have the size the
+/// configuration from `t_aux` implies.
+fn tree_r_last_roots(
+    sector_size: u64,
+    base_trees: usize,
+    cache_dir: &Path,
+    config: &StoreConfig,
+) -> Result<Vec<PoseidonDomain>, Status> {
+    let leaves = sector_size as usize / NODE_SIZE / base_trees;
+    let tree_len = get_merkle_tree_cache_size(leaves, BASE_TREE_ARITY, config.rows_to_discard)
+        .map_err(|_| Status::Invalid)?;
+
+    // the ids of `storage_proofs_core::merkle::split_config`
+    let ids: Vec<String> = match base_trees {
+        1 => vec![config.id.clone()],
+        _ => (0..base_trees)
+            .map(|i| format!("{}-{}", config.id, i))
+            .collect(),
+    };
+
+    ids.iter()
+        .map(|id| {
+            let mut file =
+                File::open(StoreConfig::data_path(cache_dir, id)).map_err(|err| io_status(&err))?;
+            let len = file.metadata().map_err(|err| io_status(&err))?.len();
+            if len != (tree_len * NODE_SIZE) as u64 {
+                return Err(Status::Invalid);
+            }
+
+            // the root is the last node of the tree
+            let mut root = [0; NODE_SIZE];
+            file.seek(SeekFrom::End(-(NODE_SIZE as i64)))
+                .and_then(|_| file.read_exact(&mut root))
+                .map_err(|err| io_status(&err))?;
+
+            to_domain(&root).ok_or(Status::Invalid)
+        })
+        .collect()
+}
+
+/// Combines the roots of the base trees the same way the tree is built from them.
+fn tree_root(mut roots: Vec<PoseidonDomain>) -> PoseidonDomain {
+    for arity in [SUB_TREE_ARITY, TOP_TREE_ARITY] {
+        if roots.len() > 1 {
+            roots = roots
+                .chunks(arity)
+                .map(|children| PoseidonFunction::default().multi_node(children, 0))
+                .collect();
+        }
+    }
+
+    roots[0]
+}
+
+fn read_file<T>(path: &Path, parse: impl FnOnce(&[u8]) -> Option<T>) -> Result<T, Status> {
+    let bytes = fs::read(path).map_err(|err| io_status(&err))?;
+    parse(&bytes).ok_or(Status::Invalid)
+}
+
+/// Decodes a node, which must be a field element.
+fn to_domain(bytes: &[u8]) -> Option<PoseidonDomain> {
+    let repr = bytes.try_into().ok()?;
+    Option::<Fr>::from(Fr::from_repr(repr)).map(Into::into)
+}
+
+fn io_status(err: &io::Error) -> Status {
+    match err.kind() {
+        io::ErrorKind::NotFound => Status::Missing,
+        _ => Status::Invalid,
+    }
+}
+
+fn status<T>(result: &Result<T, Status>) -> Status {
+    match result {
+        Ok(_) => Status::Ok,
+        Err(status) => *status,
+    }
+}
+
+fn check(passed: bool) -> Status {
+    if passed {
+        Status::Ok
+    } else {
+        Status::Invalid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECTOR_2KIB: RegisteredSealProof = RegisteredSealProof::StackedDrg2KiBV1_1;
+
+    fn node(value: u64) -> PoseidonDomain {
+        Fr::from(value).into()
+    }
+
+    /// Writes `p_aux`, `t_aux` and the TreeRLast files with the given base tree roots to `dir`,
+    /// returns the matching comm_r.
+    fn write_cache(
+        dir: &Path,
+        base_roots: &[PoseidonDomain],
+        leaves: usize,
+        rows_to_discard: usize,
+    ) -> [u8; 32] {
+        // the path is the one at sealing time, the cache may have been moved since
+        let config = |id: &str| StoreConfig::new("/moved", id, rows_to_discard);
+        let t_aux = TemporaryAux {
+            labels: vec![config("layer-1"), config("layer-2")],
+            tree_d_config: config("tree-d"),
+            tree_r_last_config: config("tree-r-last"),
+            tree_c_config: config("tree-c"),
+        };
+        fs::write(dir.join(T_AUX_FILE), bincode::serialize(&t_aux).unwrap()).unwrap();
+
+        let tree_len =
+            get_merkle_tree_cache_size(leaves, BASE_TREE_ARITY, rows_to_discard).unwrap();
+        for (i, root) in base_roots.iter().enumerate() {
+            let id = match base_roots.len() {
+                1 => "tree-r-last".to_string(),
+                _ => format!("tree-r-last-{i}"),
+            };
+            let mut data = vec![0; tree_len * NODE_SIZE];
+            root.write_bytes(&mut data[(tree_len - 1) * NODE_SIZE..])
+                .unwrap();
+            fs::write(StoreConfig::data_path(dir, &id), data).unwrap();
+        }
+
+        let comm_c = node(1);
+        let comm_r_last = tree_root(base_roots.to_vec());
+        let p_aux = [comm_c.into_bytes(), comm_r_last.into_bytes()].concat();
+        fs::write(dir.join(P_AUX_FILE), p_aux).unwrap();
+
+        PoseidonFunction::hash2(&comm_c, &comm_r_last)
+            .into_bytes()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn usable_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let comm_r = write_cache(dir.path(), &[node(2)], 64, 0);
+        let replica = dir.path().join("sealed");
+        File::create(&replica).unwrap().set_len(2048).unwrap();
+
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &comm_r);
+        assert_eq!(
+            report,
+            SectorCacheCheck {
+                replica: Status::Ok,
+                p_aux: Status::Ok,
+                t_aux: Status::Ok,
+                tree_r_last_files: Status::Ok,
+                tree_r_last_root: Status::Ok,
+                comm_r: Status::Ok,
+            }
+        );
+
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &[0; 32]);
+        assert_eq!(report.comm_r, Status::Invalid);
+        assert_eq!(report.tree_r_last_root, Status::Ok);
+    }
+
+    #[test]
+    fn broken_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let comm_r = write_cache(dir.path(), &[node(2)], 64, 0);
+        let replica = dir.path().join("sealed");
+        let tree = StoreConfig::data_path(dir.path(), "tree-r-last");
+
+        // a truncated tree and a missing replica
+        let tree_file = File::options().write(true).open(&tree).unwrap();
+        tree_file.set_len(NODE_SIZE as u64).unwrap();
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &comm_r);
+        assert_eq!(report.replica, Status::Missing);
+        assert_eq!(report.tree_r_last_files, Status::Invalid);
+        assert_eq!(report.tree_r_last_root, Status::Skipped);
+        assert_eq!(report.comm_r, Status::Ok);
+
+        // a tree with a different root
+        let comm_r = write_cache(dir.path(), &[node(2)], 64, 0);
+        write_cache(dir.path(), &[node(3)], 64, 0);
+        let mut p_aux = fs::read(dir.path().join(P_AUX_FILE)).unwrap();
+        p_aux[NODE_SIZE..].copy_from_slice(&tree_root(vec![node(2)]).into_bytes());
+        fs::write(dir.path().join(P_AUX_FILE), &p_aux).unwrap();
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &comm_r);
+        assert_eq!(report.tree_r_last_files, Status::Ok);
+        assert_eq!(report.tree_r_last_root, Status::Invalid);
+        assert_eq!(report.comm_r, Status::Ok);
+
+        // `p_aux` with a value that isn't a field element, and invalid `t_aux`
+        fs::write(dir.path().join(P_AUX_FILE), [0xff; 2 * NODE_SIZE]).unwrap();
+        fs::write(dir.path().join(T_AUX_FILE), b"t_aux").unwrap();
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &comm_r);
+        assert_eq!(report.p_aux, Status::Invalid);
+        assert_eq!(report.t_aux, Status::Invalid);
+        assert_eq!(report.tree_r_last_files, Status::Invalid);
+        assert_eq!(report.tree_r_last_root, Status::Skipped);
+        assert_eq!(report.comm_r, Status::Skipped);
+
+        // missing files
+        fs::remove_file(dir.path().join(P_AUX_FILE)).unwrap();
+        fs::remove_file(dir.path().join(T_AUX_FILE)).unwrap();
+        let report = check_sector_cache(SECTOR_2KIB, dir.path(), &replica, &comm_r);
+        assert_eq!(report.p_aux, Status::Missing);
+        assert_eq!(report.t_aux, Status::Missing);
+        assert_eq!(report.tree_r_last_files, Status::Missing);
+    }
+
+    #[test]
+    fn compound_trees() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots: Vec<_> = (0..16).map(node).collect();
+        // only the top rows of the trees are kept, so the files are small
+        let comm_r = write_cache(dir.path(), &roots, 1 << 27, 7);
+
+        let sector_64gib = RegisteredSealProof::StackedDrg64GiBV1_1;
+        let report = check_sector_cache(sector_64gib, dir.path(), Path::new("/missing"), &comm_r);
+        assert_eq!(report.tree_r_last_files, Status::Ok);
+        assert_eq!(report.tree_r_last_root, Status::Ok);
+        assert_eq!(report.comm_r, Status::Ok);
+
+        let sub_trees: Vec<_> = roots
+            .chunks(SUB_TREE_ARITY)
+            .map(|children| PoseidonFunction::default().multi_node(children, 0))
+            .collect();
+        assert_eq!(
+            tree_root(roots),
+            PoseidonFunction::default().multi_node(&sub_trees, 0)
+        );
+
+        // the 32GiB shape has base trees of the same size, but only half as many
+        let report = check_sector_cache(
+            RegisteredSealProof::StackedDrg32GiBV1_1,
+            dir.path(),
+            Path::new("/missing"),
+            &comm_r,
+        );
+        assert_eq!(report.tree_r_last_files, Status::Ok);
+        assert_eq!(report.tree_r_last_root, Status::Invalid);
+    }
+}
@@ -0,0 +1,87 @@
+//! Cooperative cancellation of long running proving and sealing operations.
+//!
+//! Operations check their token between the steps they consist of, and return a response with
+//! the [`FCPResponseStatus::Cancelled`] status once it is cancelled. A step that is running when
+//! the token is cancelled is completed first, as the proofs can't be interrupted.
+//!
+//! Window PoSt generates the vanilla proofs sector by sector and checks the token after each
+//! sector. Commit phase 2 and encoding an empty sector update are single calls into the proofs,
+//! so they only check the token before they start and once they are done. An in-flight SNARK or
+//! encoding always runs to completion, a cancellation while it runs only discards its result.
+//!
+//! [`FCPResponseStatus::Cancelled`]: crate::util::types::FCPResponseStatus::Cancelled
+
+use std::sync::atomic::Ordering;
+
+use safer_ffi::prelude::*;
+
+use super::types::CancellationToken;
+use crate::destructor;
+use crate::util::types::Cancelled;
+
+/// Create a new cancellation token, that is not cancelled.
+///
+/// The token must outlive all operations it is passed to.
+#[ffi_export]
+pub fn cancellation_token_new() -> repr_c::Box<CancellationToken> {
+    Box::<CancellationToken>::default().into()
+}
+
+/// Cancel all operations the token is passed to, including the ones started afterwards.
+///
+/// # Arguments
+///
+/// * `token` - the token, may be used from other threads at the same time
+#[ffi_export]
+pub fn cancellation_token_cancel(token: &CancellationToken) {
+    token.cancelled.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether the token was cancelled.
+#[ffi_export]
+pub fn cancellation_token_is_cancelled(token: &CancellationToken) -> bool {
+    token.is_cancelled()
+}
+
+destructor!(destroy_cancellation_token, CancellationToken);
+
+impl CancellationToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns a [`Cancelled`] error if the token was cancelled.
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks `token` if there is one.
+pub(crate) fn check(token: Option<&CancellationToken>) -> anyhow::Result<()> {
+    token.map_or(Ok(()), CancellationToken::check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_from_other_thread() {
+        let token = cancellation_token_new();
+        assert!(!cancellation_token_is_cancelled(&token));
+        assert!(check(Some(&token)).is_ok());
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| cancellation_token_cancel(&token));
+        });
+        assert!(cancellation_token_is_cancelled(&token));
+        assert!(check(Some(&token)).unwrap_err().is::<Cancelled>());
+        assert!(check(None).is_ok());
+
+        destroy_cancellation_token(token);
+    }
+}
@@ -1,11 +1,14 @@
 use std::collections::btree_map::BTreeMap;
+use std::io;
 
 use anyhow::Result;
-use filecoin_proofs_api::{self as api, SectorId};
+use filecoin_proofs_api::{self as api, SectorId, StorageProofsError};
 use safer_ffi::prelude::*;
 
-use super::types::{PrivateReplicaInfo, PublicReplicaInfo, RegisteredPoStProof};
-use crate::util::types::as_path_buf;
+use super::types::{PrivateReplicaInfo, ProofsError, PublicReplicaInfo, RegisteredPoStProof};
+use crate::util::types::{
+    as_path_buf, catch_panic_response_raw, from_anyhow, Cancelled, FCPResponseStatus,
+};
 
 #[derive(Debug, Clone)]
 struct PublicReplicaInfoTmp {
@@ -100,3 +103,169 @@ pub fn to_private_replica_info_map(
 
     Ok(map)
 }
+
+pub const GIB: u64 = 1 << 30;
+
+/// Returns the number of base trees TreeC and TreeRLast of a sector consist of, as in the sector
+/// shapes of `filecoin_proofs::constants`.
+pub fn base_tree_count(registered_proof: api::RegisteredSealProof) -> usize {
+    match u64::from(registered_proof.sector_size()) {
+        size if size >= 64 * GIB => 16,
+        size if size >= 32 * GIB => 8,
+        _ => 1,
+    }
+}
+
+/// Errors that the proofs only report as a message, by the start of the message.
+const ERROR_MESSAGES: &[(&str, ProofsError)] = &[
+    ("No cached parameters found", ProofsError::MissingParameters),
+    ("No cached srs key found", ProofsError::MissingParameters),
+    ("invalid post type provided", ProofsError::InvalidProofType),
+    (
+        "can only generate the same kind of PoSt",
+        ProofsError::InvalidProofType,
+    ),
+    ("only V1 supported", ProofsError::InvalidProofType),
+];
+
+/// Classifies an error by the first cause in its chain that is known.
+pub fn classify_error(err: &anyhow::Error) -> Option<ProofsError> {
+    err.chain().find_map(|cause| {
+        if let Some(err) = cause.downcast_ref::<ProofsError>() {
+            return Some(*err);
+        }
+        if let Some(err) = cause.downcast_ref::<StorageProofsError>() {
+            return match err {
+                StorageProofsError::FaultySectors(_) => Some(ProofsError::FaultySectors),
+                StorageProofsError::InvalidParameters(_) => Some(ProofsError::MissingParameters),
+                StorageProofsError::InvalidInputSize | StorageProofsError::OutOfBounds(..) => {
+                    Some(ProofsError::InvalidInputLength)
+                }
+                StorageProofsError::InvalidCommitment
+                | StorageProofsError::InvalidMerkleTreeArgs(..)
+                | StorageProofsError::MalformedMerkleTree
+                | StorageProofsError::MerkleTreeGenerationError(_) => {
+                    Some(ProofsError::CorruptCache)
+                }
+                // the source of an I/O error is next in the chain
+                _ => None,
+            };
+        }
+        if let Some(err) = cause.downcast_ref::<io::Error>() {
+            return match err.kind() {
+                io::ErrorKind::NotFound => Some(ProofsError::MissingFile),
+                io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData => {
+                    Some(ProofsError::CorruptCache)
+                }
+                _ => None,
+            };
+        }
+
+        let msg = cause.to_string();
+        ERROR_MESSAGES
+            .iter()
+            .find(|(prefix, _)| msg.starts_with(prefix))
+            .map(|(_, kind)| *kind)
+    })
+}
+
+/// Sets the status, error code and message of a response to the ones of `err`. The message is the
+/// plain `Display` form, without context or backtrace, which callers of the window PoSt functions
+/// match on.
+pub fn set_error<T>(response: &mut crate::util::types::Result<T>, err: &anyhow::Error) {
+    let (status_code, error_code) = match classify_error(err) {
+        Some(kind) => (kind.status(), kind as i32),
+        None if err.is::<Cancelled>() => (FCPResponseStatus::Cancelled, 0),
+        None => (FCPResponseStatus::UnclassifiedError, 0),
+    };
+
+    response.status_code = status_code;
+    response.error_code = error_code;
+    response.error_msg = err.to_string().into_bytes().into_boxed_slice().into();
+}
+
+/// Same as [`crate::util::types::catch_panic_response`], reporting the classified errors with
+/// their [`ProofsError`] code.
+pub fn catch_panic_response_classified<F, T>(
+    name: &str,
+    callback: F,
+) -> repr_c::Box<crate::util::types::Result<T>>
+where
+    T: Sized + Default,
+    F: FnOnce() -> Result<T> + std::panic::UnwindSafe,
+{
+    catch_panic_response_raw(name, || match callback() {
+        Err(err) => match classify_error(&err) {
+            Some(kind) => crate::util::types::Result::err_with_code(
+                kind.status(),
+                kind as i32,
+                format!("{err:?}").into_bytes().into_boxed_slice(),
+            ),
+            None => from_anyhow(Err(err)),
+        },
+        result => from_anyhow(result),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+
+    use super::*;
+
+    #[test]
+    fn error_classification() {
+        let missing = anyhow::Error::from(io::Error::from(io::ErrorKind::NotFound))
+            .context("failed to open replica");
+        assert_eq!(classify_error(&missing), Some(ProofsError::MissingFile));
+
+        let truncated =
+            StorageProofsError::Io(io::Error::from(io::ErrorKind::UnexpectedEof)).into();
+        assert_eq!(classify_error(&truncated), Some(ProofsError::CorruptCache));
+
+        let faulty = StorageProofsError::FaultySectors(vec![SectorId::from(42)]);
+        let faulty = Err::<(), _>(faulty).context("generating post").unwrap_err();
+        assert_eq!(classify_error(&faulty), Some(ProofsError::FaultySectors));
+
+        let params =
+            anyhow!("No cached parameters found for v28-stacked-proof [failure finding /]");
+        assert_eq!(
+            classify_error(&params),
+            Some(ProofsError::MissingParameters)
+        );
+
+        let post_type = anyhow!("invalid post type provided");
+        assert_eq!(
+            classify_error(&post_type),
+            Some(ProofsError::InvalidProofType)
+        );
+
+        assert_eq!(classify_error(&anyhow!("something else")), None);
+        assert_eq!(classify_error(&Cancelled.into()), None);
+    }
+
+    #[test]
+    fn error_responses() {
+        let response = catch_panic_response_classified("test", || -> Result<()> {
+            Err(StorageProofsError::InvalidInputSize.into())
+        });
+        assert_eq!(response.status_code, FCPResponseStatus::CallerError);
+        assert_eq!(response.error_code, ProofsError::InvalidInputLength as i32);
+
+        let response =
+            catch_panic_response_classified("test", || -> Result<()> { Err(Cancelled.into()) });
+        assert_eq!(response.status_code, FCPResponseStatus::Cancelled);
+        assert_eq!(response.error_code, 0);
+
+        let response =
+            catch_panic_response_classified("test", || -> Result<()> { Err(anyhow!("failed")) });
+        assert_eq!(response.status_code, FCPResponseStatus::UnclassifiedError);
+        assert_eq!(response.error_code, 0);
+
+        let mut response = crate::util::types::Result::ok(());
+        set_error(&mut response, &ProofsError::CorruptCache.into());
+        assert_eq!(response.status_code, FCPResponseStatus::ReceiverError);
+        assert_eq!(response.error_code, ProofsError::CorruptCache as i32);
+        assert_eq!(&response.error_msg[..], b"corrupt cache");
+    }
+}
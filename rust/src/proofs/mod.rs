@@ -1,4 +1,10 @@
 mod helpers;
 
 pub mod api;
+mod cache_check;
+pub mod cancel;
+pub mod padding;
+pub mod piece;
+pub mod progress;
 pub mod types;
+mod vanilla;
@@ -0,0 +1,369 @@
+//! Fr32 padding and unpadding of byte ranges, between file descriptors and in memory.
+//!
+//! Every 127 unpadded bytes are padded to 128 bytes, four Frs with two zero bits each. Ranges are
+//! transformed in whole blocks of these, the blocks a range only partially covers are read and
+//! written back with the data around the range.
+
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+
+use anyhow::Context;
+use filecoin_proofs::SECTOR_SIZE_64_GIB;
+use filecoin_proofs_api::fr32::{write_unpadded, Fr32Reader};
+use safer_ffi::prelude::*;
+
+use super::helpers::catch_panic_response_classified;
+use super::types::{
+    FileDescriptorRef, Fr32PadBytesResponse, Fr32PadRangeResponse, Fr32UnpadBytesResponse,
+    Fr32UnpadRangeResponse, ProofsError,
+};
+use crate::destructor;
+
+const UNPADDED_BLOCK_SIZE: u64 = 127;
+const PADDED_BLOCK_SIZE: u64 = 128;
+/// Number of blocks transformed at once, 1MiB of padded data.
+const BATCH_BLOCKS: u64 = 8192;
+/// Ranges end within the largest sector, in unpadded bytes.
+const MAX_UNPADDED_END: u64 = SECTOR_SIZE_64_GIB / PADDED_BLOCK_SIZE * UNPADDED_BLOCK_SIZE;
+
+/// Fr32 pad unpadded data into a range of padded data
+///
+/// The data around the range is kept, `dst_fd` must be readable if the range doesn't start and
+/// end at a multiple of 127 bytes. The range must end within the largest sector. The caller is
+/// responsible for closing the file descriptors.
+///
+/// # Arguments
+///
+/// * `src_fd`           - the unpadded data, read from the current position
+/// * `dst_fd`           - the padded data, which starts at the start of the file
+/// * `unpadded_offset`  - offset of the range in the unpadded data
+/// * `unpadded_len`     - length of the range, the number of bytes read from `src_fd`
+#[ffi_export]
+unsafe fn fr32_pad_range(
+    src_fd: libc::c_int,
+    dst_fd: libc::c_int,
+    unpadded_offset: u64,
+    unpadded_len: u64,
+) -> repr_c::Box<Fr32PadRangeResponse> {
+    catch_panic_response_classified("fr32_pad_range", || {
+        pad_range(
+            FileDescriptorRef::new(src_fd),
+            &mut FileDescriptorRef::new(dst_fd),
+            unpadded_offset,
+            unpadded_len,
+        )
+    })
+}
+
+/// Unpad a range of Fr32 padded data, without the need of unsealing a whole sector
+///
+/// The caller is responsible for closing the file descriptors.
+///
+/// # Arguments
+///
+/// * `src_fd`           - the padded data, which starts at the start of the file
+/// * `dst_fd`           - the unpadded range is written here, at the current position
+/// * `unpadded_offset`  - offset of the range in the unpadded data
+/// * `unpadded_len`     - length of the range
+#[ffi_export]
+unsafe fn fr32_unpad_range(
+    src_fd: libc::c_int,
+    dst_fd: libc::c_int,
+    unpadded_offset: u64,
+    unpadded_len: u64,
+) -> repr_c::Box<Fr32UnpadRangeResponse> {
+    catch_panic_response_classified("fr32_unpad_range", || {
+        unpad_range(
+            &mut FileDescriptorRef::new(src_fd),
+            &mut FileDescriptorRef::new(dst_fd),
+            unpadded_offset,
+            unpadded_len,
+        )
+    })
+}
+
+/// Same as [`fr32_pad_range`] in memory, returning `padded` with the range overwritten by
+/// `unpadded`. It is extended as needed, but the range must not start past its end, `padded` may
+/// be empty.
+#[ffi_export]
+fn fr32_pad_bytes(
+    padded: c_slice::Ref<'_, u8>,
+    unpadded: c_slice::Ref<'_, u8>,
+    unpadded_offset: u64,
+) -> repr_c::Box<Fr32PadBytesResponse> {
+    catch_panic_response_classified("fr32_pad_bytes", || {
+        let padded = pad_bytes(&padded, &unpadded, unpadded_offset)?;
+        Ok(padded.into_boxed_slice().into())
+    })
+}
+
+/// Same as [`fr32_unpad_range`] in memory, returning the unpadded range.
+#[ffi_export]
+fn fr32_unpad_bytes(
+    padded: c_slice::Ref<'_, u8>,
+    unpadded_offset: u64,
+    unpadded_len: u64,
+) -> repr_c::Box<Fr32UnpadBytesResponse> {
+    catch_panic_response_classified("fr32_unpad_bytes", || {
+        let unpadded = unpad_bytes(&padded, unpadded_offset, unpadded_len)?;
+        Ok(unpadded.into_boxed_slice().into())
+    })
+}
+
+destructor!(destroy_fr32_pad_range_response, Fr32PadRangeResponse);
+destructor!(destroy_fr32_unpad_range_response, Fr32UnpadRangeResponse);
+destructor!(destroy_fr32_pad_bytes_response, Fr32PadBytesResponse);
+destructor!(destroy_fr32_unpad_bytes_response, Fr32UnpadBytesResponse);
+
+/// Pads `len` bytes of `source` into `target`, at the unpadded `offset` of the padded data in
+/// `target`. The data around the range is read from `target`, or zeros past its end.
+pub fn pad_range<R: Read, T: Read + Write + Seek>(
+    mut source: R,
+    target: &mut T,
+    offset: u64,
+    len: u64,
+) -> anyhow::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    let end = range_end(offset, len)?;
+    let mut unpadded = Vec::new();
+    for (first_block, end_block) in batches(offset, len) {
+        let batch_start = first_block * UNPADDED_BLOCK_SIZE;
+        let batch_end = end_block * UNPADDED_BLOCK_SIZE;
+        unpadded.clear();
+        unpadded.resize((batch_end - batch_start) as usize, 0);
+
+        if offset > batch_start {
+            read_block(target, first_block, &mut unpadded)?;
+        }
+        if end < batch_end {
+            let last = unpadded.len() - UNPADDED_BLOCK_SIZE as usize;
+            read_block(target, end_block - 1, &mut unpadded[last..])?;
+        }
+
+        let from = offset.max(batch_start) - batch_start;
+        let to = end.min(batch_end) - batch_start;
+        read_input(&mut source, &mut unpadded[from as usize..to as usize])?;
+
+        target.seek(SeekFrom::Start(first_block * PADDED_BLOCK_SIZE))?;
+        target.write_all(&fr32_pad(&unpadded))?;
+    }
+
+    Ok(())
+}
+
+/// Unpads `len` bytes at the unpadded `offset` of the padded data in `source` into `target`.
+pub fn unpad_range<S: Read + Seek, W: Write>(
+    source: &mut S,
+    target: &mut W,
+    offset: u64,
+    len: u64,
+) -> anyhow::Result<()> {
+    if len == 0 {
+        return Ok(());
+    }
+    let end = range_end(offset, len)?;
+    let mut padded = Vec::new();
+    let mut unpadded = Vec::new();
+    for (first_block, end_block) in batches(offset, len) {
+        let batch_start = first_block * UNPADDED_BLOCK_SIZE;
+        let batch_end = end_block * UNPADDED_BLOCK_SIZE;
+        padded.resize(((end_block - first_block) * PADDED_BLOCK_SIZE) as usize, 0);
+        source.seek(SeekFrom::Start(first_block * PADDED_BLOCK_SIZE))?;
+        read_input(source, &mut padded)?;
+
+        unpadded.clear();
+        write_unpadded(
+            &padded,
+            &mut unpadded,
+            0,
+            (batch_end - batch_start) as usize,
+        )?;
+
+        let from = offset.max(batch_start) - batch_start;
+        let to = end.min(batch_end) - batch_start;
+        target.write_all(&unpadded[from as usize..to as usize])?;
+    }
+
+    Ok(())
+}
+
+/// Same as [`pad_range`] in memory, returning `padded` with the range overwritten.
+pub fn pad_bytes(padded: &[u8], unpadded: &[u8], offset: u64) -> anyhow::Result<Vec<u8>> {
+    // a partial last block counts as whole, it is read with zeros past the end
+    let blocks = (padded.len() as u64).div_ceil(PADDED_BLOCK_SIZE);
+    if offset > blocks * UNPADDED_BLOCK_SIZE {
+        return Err(ProofsError::InvalidInputLength).context("the range starts past the end");
+    }
+
+    let mut target = Cursor::new(padded.to_vec());
+    pad_range(unpadded, &mut target, offset, unpadded.len() as u64)?;
+
+    Ok(target.into_inner())
+}
+
+/// Same as [`unpad_range`] in memory.
+pub fn unpad_bytes(padded: &[u8], offset: u64, len: u64) -> anyhow::Result<Vec<u8>> {
+    let blocks = padded.len() as u64 / PADDED_BLOCK_SIZE;
+    if range_end(offset, len)? > blocks * UNPADDED_BLOCK_SIZE {
+        return Err(ProofsError::InvalidInputLength).context("the input ends before the range");
+    }
+
+    let mut unpadded = Vec::with_capacity(len as usize);
+    unpad_range(&mut Cursor::new(padded), &mut unpadded, offset, len)?;
+
+    Ok(unpadded)
+}
+
+/// Pads whole blocks of unpadded data.
+pub(crate) fn fr32_pad(unpadded: &[u8]) -> Vec<u8> {
+    let blocks = unpadded.len() / UNPADDED_BLOCK_SIZE as usize;
+    let mut padded = Vec::with_capacity(blocks * PADDED_BLOCK_SIZE as usize);
+    Fr32Reader::new(unpadded)
+        .read_to_end(&mut padded)
+        .expect("reading from memory can't fail");
+
+    padded
+}
+
+/// Returns the end of an unpadded range, which must be within the largest sector.
+fn range_end(offset: u64, len: u64) -> anyhow::Result<u64> {
+    match offset.checked_add(len) {
+        Some(end) if end <= MAX_UNPADDED_END => Ok(end),
+        _ => Err(ProofsError::InvalidInputLength).context("the range ends past the largest sector"),
+    }
+}
+
+/// Splits the blocks covering an unpadded range into batches of at most [`BATCH_BLOCKS`],
+/// returning the first and end block of each.
+fn batches(offset: u64, len: u64) -> impl Iterator<Item = (u64, u64)> {
+    let first_block = offset / UNPADDED_BLOCK_SIZE;
+    let end_block = (offset + len).div_ceil(UNPADDED_BLOCK_SIZE);
+
+    (first_block..end_block)
+        .step_by(BATCH_BLOCKS as usize)
+        .map(move |block| (block, (block + BATCH_BLOCKS).min(end_block)))
+}
+
+/// Reads the unpadded data of a block of `target` into the start of `unpadded`.
+fn read_block<T: Read + Seek>(target: &mut T, block: u64, unpadded: &mut [u8]) -> io::Result<()> {
+    let mut padded = Vec::with_capacity(PADDED_BLOCK_SIZE as usize);
+    target.seek(SeekFrom::Start(block * PADDED_BLOCK_SIZE))?;
+    target
+        .by_ref()
+        .take(PADDED_BLOCK_SIZE)
+        .read_to_end(&mut padded)?;
+    padded.resize(PADDED_BLOCK_SIZE as usize, 0);
+
+    let mut block_data = &mut unpadded[..UNPADDED_BLOCK_SIZE as usize];
+    write_unpadded(&padded, &mut block_data, 0, UNPADDED_BLOCK_SIZE as usize)?;
+
+    Ok(())
+}
+
+/// Fills `buf` from `source`, which is an invalid input length if it ends before.
+fn read_input(source: &mut impl Read, buf: &mut [u8]) -> anyhow::Result<()> {
+    source.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => anyhow::Error::new(ProofsError::InvalidInputLength)
+            .context("the input ends before the range"),
+        _ => err.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::io::AsRawFd;
+
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+    use crate::proofs::helpers::classify_error;
+    use crate::util::types::FCPResponseStatus;
+
+    fn random_bytes(seed: u8, len: usize) -> Vec<u8> {
+        let rng = &mut ChaChaRng::from_seed([seed; 32]);
+        (0..len).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn unaligned_ranges() {
+        let data = random_bytes(1, 127 * 9000 + 50);
+        let padded = pad_bytes(&[], &data, 0).unwrap();
+        let mut whole_blocks = data.clone();
+        whole_blocks.resize(127 * 9001, 0);
+        assert_eq!(padded, fr32_pad(&whole_blocks));
+
+        for (offset, len) in [
+            (0, 127),
+            (1, 1),
+            (100, 200),
+            (126, 127 * 8500),
+            (1_000_000, 50),
+        ] {
+            let range = offset as usize..(offset + len) as usize;
+            assert_eq!(
+                unpad_bytes(&padded, offset, len).unwrap(),
+                data[range.clone()]
+            );
+
+            // overwriting a range keeps the data around it
+            let other = random_bytes(2, len as usize);
+            let mut expected = data.clone();
+            expected[range].copy_from_slice(&other);
+            let overwritten = pad_bytes(&padded, &other, offset).unwrap();
+            assert_eq!(overwritten, pad_bytes(&[], &expected, 0).unwrap());
+        }
+
+        // past the end of the padded data
+        for (offset, len) in [(data.len() as u64, 200), (u64::MAX, 1), (1, u64::MAX)] {
+            let err = unpad_bytes(&padded, offset, len).unwrap_err();
+            assert_eq!(classify_error(&err), Some(ProofsError::InvalidInputLength));
+        }
+        let extended = pad_bytes(&padded[..256], &data[..56], 250).unwrap();
+        assert_eq!(extended.len(), 384);
+        assert_eq!(unpad_bytes(&extended, 0, 250).unwrap(), data[..250]);
+        assert_eq!(unpad_bytes(&extended, 250, 56).unwrap(), data[..56]);
+        let extended = pad_bytes(&padded[..200], &data[..10], 200).unwrap();
+        assert_eq!(unpad_bytes(&extended, 0, 127).unwrap(), data[..127]);
+        assert_eq!(unpad_bytes(&extended, 200, 10).unwrap(), data[..10]);
+        for offset in [300, u64::MAX] {
+            let err = pad_bytes(&padded[..256], &data[..10], offset).unwrap_err();
+            assert_eq!(classify_error(&err), Some(ProofsError::InvalidInputLength));
+        }
+
+        // empty ranges don't touch the data
+        assert_eq!(pad_bytes(&padded[..256], &[], 100).unwrap(), padded[..256]);
+        assert!(pad_bytes(&[], &[], 0).unwrap().is_empty());
+        assert!(unpad_bytes(&padded, 100, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn file_descriptors() {
+        let data = random_bytes(3, 5000);
+        let mut unpadded = tempfile::tempfile().unwrap();
+        unpadded.write_all(&data[1000..3000]).unwrap();
+        unpadded.rewind().unwrap();
+        let mut padded = tempfile::tempfile().unwrap();
+        padded
+            .write_all(&pad_bytes(&[], &data, 0).unwrap())
+            .unwrap();
+
+        // replace the range with itself
+        let response =
+            unsafe { fr32_pad_range(unpadded.as_raw_fd(), padded.as_raw_fd(), 1000, 2000) };
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+
+        let mut output = tempfile::tempfile().unwrap();
+        let response = unsafe { fr32_unpad_range(padded.as_raw_fd(), output.as_raw_fd(), 3, 4990) };
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        output.rewind().unwrap();
+        let mut unpadded = Vec::new();
+        output.read_to_end(&mut unpadded).unwrap();
+        assert_eq!(unpadded, data[3..4993]);
+
+        let response = fr32_unpad_bytes([0; 128][..].into(), 0, 128);
+        assert_eq!(response.status_code, FCPResponseStatus::CallerError);
+        assert_eq!(response.error_code, ProofsError::InvalidInputLength as i32);
+    }
+}
@@ -0,0 +1,362 @@
+//! Incremental piece commitments, for data that is received in parts, like from a network stream.
+//!
+//! The data is Fr32 padded and hashed in chunks, the chunks of a batch in parallel. Only the roots
+//! of the hashed chunks are kept, so the data doesn't need to be stored anywhere.
+
+use anyhow::Context;
+use filecoin_proofs::{
+    MINIMUM_RESERVED_BYTES_FOR_PIECE_IN_FULLY_ALIGNED_SECTOR as MINIMUM_PIECE_SIZE,
+    SECTOR_SIZE_64_GIB,
+};
+use filecoin_proofs_api::fr32::to_padded_bytes;
+use filecoin_proofs_api::NODE_SIZE;
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use safer_ffi::prelude::*;
+use sha2::{Digest, Sha256};
+
+use super::helpers::catch_panic_response_classified;
+use super::padding::fr32_pad;
+use super::types::{
+    PieceCommitment, PieceCommitmentHasher, PieceCommitmentHasherResponse, PieceCommitmentResponse,
+    ProofsError,
+};
+use crate::destructor;
+
+type Node = [u8; NODE_SIZE];
+
+/// Tree level of the chunks the data is hashed in, a chunk is 1MiB of padded data.
+const CHUNK_LEVEL: usize = 15;
+const PADDED_CHUNK_SIZE: usize = NODE_SIZE << CHUNK_LEVEL;
+/// 127 unpadded bytes are padded to 128 bytes.
+const UNPADDED_CHUNK_SIZE: usize = PADDED_CHUNK_SIZE / 128 * 127;
+/// Highest tree level supported, far more than the largest sector has.
+const MAX_LEVEL: usize = 48;
+/// A piece fills at most the largest sector, in padded bytes.
+const MAX_PIECE_SIZE: u64 = SECTOR_SIZE_64_GIB;
+
+lazy_static! {
+    /// Roots of the trees of zeros, by level.
+    static ref ZERO_ROOTS: Vec<Node> = {
+        let mut roots = vec![[0; NODE_SIZE]];
+        for level in 1..=MAX_LEVEL {
+            let below = roots[level - 1];
+            roots.push(hash(&below, &below));
+        }
+        roots
+    };
+}
+
+/// Create a hasher computing the commitment of a piece from data written to it in parts
+///
+/// The result is the same as of [`generate_piece_commitment`] for the concatenated data.
+///
+/// # Arguments
+///
+/// * `unpadded_size_hint` - the piece size if it is known up front, 0 otherwise. The data is
+///   padded with zeros to at least this size, so it may be sent without the trailing zeros. It
+///   must not be larger than the largest sector. With a hint, no data needs to be written at
+///   all, the commitment is then the one of a piece of zeros.
+///
+/// [`generate_piece_commitment`]: super::api::generate_piece_commitment
+#[ffi_export]
+pub fn piece_commitment_hasher_create(
+    unpadded_size_hint: u64,
+) -> repr_c::Box<PieceCommitmentHasherResponse> {
+    catch_panic_response_classified("piece_commitment_hasher_create", || {
+        let hasher = PieceCommitmentHasher::new(unpadded_size_hint)?;
+        Ok(Some(Box::new(hasher).into()))
+    })
+}
+
+/// Hash the next part of the data. Hashing is done in batches, so most calls only buffer it.
+#[ffi_export]
+pub fn piece_commitment_hasher_write(
+    hasher: &mut PieceCommitmentHasher,
+    data: c_slice::Ref<'_, u8>,
+) {
+    hasher.write(&data);
+}
+
+/// Hash the rest of the data, returning the piece commitment and the padded size of the piece.
+///
+/// Fails with [`ProofsError::InvalidInputLength`] if no data was written and no size was hinted.
+/// The hasher is consumed, it must not be used or destroyed afterwards.
+#[ffi_export]
+pub fn piece_commitment_hasher_finalize(
+    hasher: repr_c::Box<PieceCommitmentHasher>,
+) -> repr_c::Box<PieceCommitmentResponse> {
+    catch_panic_response_classified("piece_commitment_hasher_finalize", || {
+        let hasher: Box<PieceCommitmentHasher> = hasher.into();
+        hasher.finalize()
+    })
+}
+
+destructor!(destroy_piece_commitment_hasher, PieceCommitmentHasher);
+destructor!(
+    destroy_piece_commitment_hasher_response,
+    PieceCommitmentHasherResponse
+);
+destructor!(destroy_piece_commitment_response, PieceCommitmentResponse);
+
+impl PieceCommitmentHasher {
+    pub fn new(unpadded_size_hint: u64) -> anyhow::Result<Self> {
+        check_size(unpadded_size_hint).context("the size hint is too large")?;
+
+        Ok(PieceCommitmentHasher {
+            min_padded_size: (unpadded_size_hint > 0)
+                .then(|| piece_size(unpadded_size_hint.max(MINIMUM_PIECE_SIZE))),
+            written: 0,
+            buffer: Vec::new(),
+            roots: Vec::new(),
+            batch_size: UNPADDED_CHUNK_SIZE * rayon::current_num_threads(),
+        })
+    }
+
+    pub fn write(&mut self, mut data: &[u8]) {
+        self.written += data.len() as u64;
+
+        while !data.is_empty() {
+            let (head, rest) = data.split_at(data.len().min(self.batch_size - self.buffer.len()));
+            self.buffer.extend_from_slice(head);
+            data = rest;
+
+            if self.buffer.len() == self.batch_size {
+                self.hash_buffer();
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> anyhow::Result<PieceCommitment> {
+        if self.written == 0 && self.min_padded_size.is_none() {
+            return Err(ProofsError::InvalidInputLength)
+                .context("no data was written and no size was hinted");
+        }
+        check_size(self.written).context("more data was written than fits into a sector")?;
+
+        let padded_size = self
+            .min_padded_size
+            .unwrap_or(0)
+            .max(piece_size(self.written.max(MINIMUM_PIECE_SIZE)));
+        let level = (padded_size / NODE_SIZE as u64).trailing_zeros() as usize;
+
+        // pad the last Fr with zeros
+        self.buffer
+            .resize(self.buffer.len().next_multiple_of(127), 0);
+        let comm_p = if level < CHUNK_LEVEL {
+            // smaller than a chunk, so nothing was hashed yet
+            root(&fr32_pad(&self.buffer), level)
+        } else {
+            self.hash_buffer();
+            self.root(level)
+        };
+
+        Ok(PieceCommitment {
+            comm_p,
+            padded_size,
+        })
+    }
+
+    /// Hashes the buffered data in chunks, which must be whole Frs.
+    fn hash_buffer(&mut self) {
+        let roots: Vec<Node> = self
+            .buffer
+            .par_chunks(UNPADDED_CHUNK_SIZE)
+            .map(|chunk| root(&fr32_pad(chunk), CHUNK_LEVEL))
+            .collect();
+        self.buffer.clear();
+
+        for root in roots {
+            self.push(root);
+        }
+    }
+
+    /// Adds the root of the next chunk, combining the roots of equal levels.
+    fn push(&mut self, mut root: Node) {
+        for slot in self.roots.iter_mut() {
+            match slot.take() {
+                Some(left) => root = hash(&left, &root),
+                None => {
+                    *slot = Some(root);
+                    return;
+                }
+            }
+        }
+        self.roots.push(Some(root));
+    }
+
+    /// Combines the roots of the chunks into the root of a tree of `level`, padded with zeros.
+    fn root(&self, level: usize) -> Node {
+        let slot = |i: usize| self.roots.get(i).copied().flatten();
+
+        let mut right: Option<Node> = None;
+        for i in 0..level - CHUNK_LEVEL {
+            let zero = &ZERO_ROOTS[CHUNK_LEVEL + i];
+            right = match (slot(i), right) {
+                (Some(left), right) => Some(hash(&left, right.as_ref().unwrap_or(zero))),
+                (None, Some(left)) => Some(hash(&left, zero)),
+                (None, None) => None,
+            };
+        }
+
+        slot(level - CHUNK_LEVEL)
+            .or(right)
+            .unwrap_or(ZERO_ROOTS[level])
+    }
+}
+
+/// Checks that `unpadded` bytes fit into the largest piece.
+fn check_size(unpadded: u64) -> anyhow::Result<()> {
+    if unpadded > MAX_PIECE_SIZE / 128 * 127 {
+        return Err(ProofsError::InvalidInputLength.into());
+    }
+
+    Ok(())
+}
+
+/// Returns the padded size of the smallest piece holding `unpadded` bytes.
+fn piece_size(unpadded: u64) -> u64 {
+    (to_padded_bytes(unpadded as usize) as u64).next_power_of_two()
+}
+
+/// Returns the root of a tree of `level` over `data`, padded with zeros.
+fn root(data: &[u8], level: usize) -> Node {
+    if data.is_empty() {
+        return ZERO_ROOTS[level];
+    }
+    if level == 0 {
+        return data.try_into().expect("a single node");
+    }
+
+    let (left, right) = data.split_at(data.len().min(NODE_SIZE << (level - 1)));
+    hash(&root(left, level - 1), &root(right, level - 1))
+}
+
+/// sha256 truncated to 254 bits, the hash function of piece commitments.
+fn hash(left: &Node, right: &Node) -> Node {
+    let mut node: Node = Sha256::new()
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into();
+    node[NODE_SIZE - 1] &= 0b0011_1111;
+
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use filecoin_proofs_api::seal::generate_piece_commitment;
+    use filecoin_proofs_api::{RegisteredSealProof, UnpaddedBytesAmount};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    /// The commitment of `data` padded with zeros to a piece of `unpadded_size`.
+    fn expected(data: &[u8], unpadded_size: u64) -> [u8; 32] {
+        let mut piece = data.to_vec();
+        piece.resize(unpadded_size as usize, 0);
+        generate_piece_commitment(
+            RegisteredSealProof::StackedDrg512MiBV1_1,
+            Cursor::new(piece),
+            UnpaddedBytesAmount(unpadded_size),
+        )
+        .unwrap()
+        .commitment
+    }
+
+    fn create(hint: u64) -> repr_c::Box<PieceCommitmentHasher> {
+        let mut response = piece_commitment_hasher_create(hint);
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        response.value.take().unwrap()
+    }
+
+    fn commit(data: &[u8], part_size: usize, hint: u64) -> PieceCommitment {
+        let mut hasher = create(hint);
+        for part in data.chunks(part_size) {
+            piece_commitment_hasher_write(&mut hasher, part.into());
+        }
+        let response = piece_commitment_hasher_finalize(hasher);
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+
+        PieceCommitment {
+            comm_p: response.comm_p,
+            padded_size: response.padded_size,
+        }
+    }
+
+    #[test]
+    fn small_pieces() {
+        let rng = &mut ChaChaRng::from_seed([1; 32]);
+        let data: Vec<u8> = (0..2032).map(|_| rng.gen()).collect();
+
+        let result = commit(&data, 100, 0);
+        assert_eq!(result.comm_p, expected(&data, 2032));
+        assert_eq!(result.padded_size, 2048);
+
+        // padded to the next piece size, or the hinted one
+        let result = commit(&data[..1000], 1000, 0);
+        assert_eq!(result.comm_p, expected(&data[..1000], 1016));
+        assert_eq!(result.padded_size, 1024);
+        let result = commit(&data[..1000], 7, 4064);
+        assert_eq!(result.comm_p, expected(&data[..1000], 4064));
+        assert_eq!(result.padded_size, 4096);
+        let result = commit(&data[..1], 1, 0);
+        assert_eq!(result.comm_p, expected(&data[..1], 127));
+        assert_eq!(result.padded_size, 128);
+
+        // without data the piece is all zeros, but only with a size hint
+        let response = piece_commitment_hasher_finalize(create(2032));
+        assert_eq!(response.status_code, FCPResponseStatus::NoError);
+        assert_eq!(response.comm_p, expected(&[], 2032));
+        assert_eq!(response.padded_size, 2048);
+        let response = piece_commitment_hasher_finalize(create(0));
+        assert_eq!(response.status_code, FCPResponseStatus::CallerError);
+        assert_eq!(response.error_code, ProofsError::InvalidInputLength as i32);
+
+        // the largest piece fills a 64GiB sector
+        drop(create(MAX_PIECE_SIZE / 128 * 127));
+        for hint in [MAX_PIECE_SIZE, u64::MAX] {
+            let response = piece_commitment_hasher_create(hint);
+            assert_eq!(response.status_code, FCPResponseStatus::CallerError);
+            assert_eq!(response.error_code, ProofsError::InvalidInputLength as i32);
+            assert!(response.value.is_none());
+        }
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let rng = &mut ChaChaRng::from_seed([2; 32]);
+        let data: Vec<u8> = (0..3 * UNPADDED_CHUNK_SIZE + 4321)
+            .map(|_| rng.gen())
+            .collect();
+        let piece_size = 4 * UNPADDED_CHUNK_SIZE as u64;
+        let comm_p = expected(&data, piece_size);
+
+        let result = commit(&data, 1 << 16, 0);
+        assert_eq!(result.comm_p, comm_p);
+        assert_eq!(result.padded_size, 4 * PADDED_CHUNK_SIZE as u64);
+
+        // a single thread hashes a chunk per batch
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let result = pool.install(|| commit(&data, 1 << 20, 0));
+        assert_eq!(result.comm_p, comm_p);
+
+        // the batch size is the one of the pool the hasher was created in
+        let mut hasher = pool.install(|| create(0));
+        piece_commitment_hasher_write(&mut hasher, data[..].into());
+        assert_eq!(piece_commitment_hasher_finalize(hasher).comm_p, comm_p);
+
+        let hinted = 2 * piece_size;
+        let result = pool.install(|| commit(&data, 1 << 20, hinted));
+        assert_eq!(result.comm_p, expected(&data, hinted));
+        assert_eq!(result.padded_size, 8 * PADDED_CHUNK_SIZE as u64);
+    }
+}
@@ -0,0 +1,305 @@
+//! Progress reporting of long running sealing operations.
+//!
+//! The proofs don't report progress themselves, so it is derived from the files they write. While
+//! an operation runs, its output directory is polled from a separate thread, the label layers and
+//! trees appear there one after the other.
+//!
+//! Commit phase 2 proves all partitions of a sector in a single batch and writes nothing while
+//! doing so. The proofs give no insight into the batch, so it is only reported as started and as
+//! done, with no events in between.
+
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use filecoin_proofs::constants::LAYERS;
+use filecoin_proofs_api::RegisteredSealProof;
+use safer_ffi::closure::RefDynFnMut1;
+
+use super::helpers::base_tree_count;
+use super::types::{SealProgress, SealProgressKind};
+
+/// Called with each progress event of an operation, see [`SealProgress`].
+pub type SealProgressCallback<'a> = RefDynFnMut1<'a, (), SealProgress>;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Prefix of the label layer files, followed by the 1-based layer number.
+const LAYER_FILE_PREFIX: &str = "sc-02-data-layer-";
+/// Prefixes of the TreeC and TreeRLast files, there is one per base tree of a sector.
+const TREE_FILE_PREFIXES: [&str; 2] = ["sc-02-data-tree-c", "sc-02-data-tree-r-last"];
+const DATA_FILE_SUFFIX: &str = ".dat";
+
+/// What an operation produces, which determines how its progress is tracked.
+pub(crate) enum Stage<'a> {
+    /// SDR, writing the label layers to `dir` one after the other.
+    Layers { dir: &'a Path, layers: u64 },
+    /// Tree building, writing TreeC and TreeRLast to `dir`.
+    Trees { dir: &'a Path, files: u64 },
+    /// Proving, all partitions are proven in a single batch.
+    Partitions { partitions: u64 },
+}
+
+impl<'a> Stage<'a> {
+    pub(crate) fn layers(registered_proof: RegisteredSealProof, dir: &'a Path) -> Self {
+        let sector_size = u64::from(registered_proof.sector_size());
+        let layers = LAYERS
+            .read()
+            .expect("LAYERS poisoned")
+            .get(&sector_size)
+            .copied()
+            .expect("unknown sector size") as u64;
+
+        Stage::Layers { dir, layers }
+    }
+
+    pub(crate) fn trees(registered_proof: RegisteredSealProof, dir: &'a Path) -> Self {
+        Stage::Trees {
+            dir,
+            files: 2 * base_tree_count(registered_proof) as u64,
+        }
+    }
+
+    pub(crate) fn partitions(registered_proof: RegisteredSealProof) -> Self {
+        Stage::Partitions {
+            partitions: registered_proof.partitions().into(),
+        }
+    }
+
+    /// Returns the number of completed units of work. The newest file may still be written, so
+    /// it only counts once the next one appears.
+    fn completed(&self) -> u64 {
+        match self {
+            Stage::Layers { dir, layers } => {
+                let written = (1..=*layers)
+                    .take_while(|layer| {
+                        dir.join(format!("{LAYER_FILE_PREFIX}{layer}{DATA_FILE_SUFFIX}"))
+                            .exists()
+                    })
+                    .count() as u64;
+                written.saturating_sub(1)
+            }
+            Stage::Trees { dir, files } => {
+                let written = fs::read_dir(dir)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                            .filter(|name| {
+                                name.ends_with(DATA_FILE_SUFFIX)
+                                    && TREE_FILE_PREFIXES
+                                        .iter()
+                                        .any(|prefix| name.starts_with(prefix))
+                            })
+                            .count() as u64
+                    })
+                    .unwrap_or_default();
+                written.saturating_sub(1).min(files - 1)
+            }
+            Stage::Partitions { .. } => 0,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        match self {
+            Stage::Layers { layers, .. } => *layers,
+            Stage::Trees { files, .. } => *files,
+            Stage::Partitions { partitions } => *partitions,
+        }
+    }
+
+    /// Reports that the work started, for stages that have no intermediate progress.
+    fn start(&self, callback: &mut SealProgressCallback<'_>) {
+        if let Stage::Partitions { partitions } = self {
+            callback.call(SealProgress {
+                kind: SealProgressKind::PartitionProofDone,
+                done: 0,
+                total: *partitions,
+            });
+        }
+    }
+
+    /// Reports the progress from `reported` to `completed` units of work.
+    fn report(&self, callback: &mut SealProgressCallback<'_>, reported: u64, completed: u64) {
+        if completed <= reported {
+            return;
+        }
+        let total = self.total();
+        match self {
+            Stage::Layers { .. } => {
+                for layer in reported + 1..=completed {
+                    callback.call(SealProgress {
+                        kind: SealProgressKind::LayerDone,
+                        done: layer,
+                        total,
+                    });
+                }
+            }
+            Stage::Trees { .. } => callback.call(SealProgress {
+                kind: SealProgressKind::TreeBuildPercentage,
+                done: completed * 100 / total,
+                total: 100,
+            }),
+            Stage::Partitions { .. } => callback.call(SealProgress {
+                kind: SealProgressKind::PartitionProofDone,
+                done: completed,
+                total,
+            }),
+        }
+    }
+}
+
+/// Runs an operation, reporting its progress to `callback` if there is one.
+///
+/// The callback is called from the calling thread and a separate one, but never concurrently. The
+/// final events are only reported if the operation succeeds.
+pub(crate) fn track<T>(
+    callback: Option<SealProgressCallback<'_>>,
+    stage: Stage<'_>,
+    operation: impl FnOnce() -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let Some(mut callback) = callback else {
+        return operation();
+    };
+    stage.start(&mut callback);
+
+    let (stop, stopped) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        let stage = &stage;
+        let monitor = scope.spawn(move || {
+            let mut reported = 0;
+            loop {
+                let completed = stage.completed();
+                stage.report(&mut callback, reported, completed);
+                reported = reported.max(completed);
+
+                match stopped.recv_timeout(POLL_INTERVAL) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => return (callback, reported),
+                }
+            }
+        });
+
+        let result = operation();
+        drop(stop);
+        let (mut callback, reported) = monitor.join().expect("progress monitor panicked");
+        if result.is_ok() {
+            stage.report(&mut callback, reported, stage.total());
+        }
+
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn record(
+        stage: Stage<'_>,
+        operation: impl FnOnce() -> anyhow::Result<()>,
+    ) -> (anyhow::Result<()>, Vec<SealProgress>) {
+        let mut events = Vec::new();
+        let mut push = |progress| events.push(progress);
+        let result = track(Some(RefDynFnMut1::new(&mut push)), stage, operation);
+
+        (result, events)
+    }
+
+    fn layer(done: u64) -> SealProgress {
+        SealProgress {
+            kind: SealProgressKind::LayerDone,
+            done,
+            total: 2,
+        }
+    }
+
+    /// Writes `name` to `dir` and waits until the monitor had the chance to see it.
+    fn write(dir: &Path, name: &str) {
+        fs::write(dir.join(name), b"").unwrap();
+        thread::sleep(POLL_INTERVAL + Duration::from_millis(200));
+    }
+
+    #[test]
+    fn layer_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let stage = Stage::layers(RegisteredSealProof::StackedDrg2KiBV1_1, dir.path());
+
+        let (result, events) = record(stage, || {
+            write(dir.path(), "sc-02-data-layer-1.dat");
+            write(dir.path(), "sc-02-data-layer-2.dat");
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(events, vec![layer(1), layer(2)]);
+
+        // a failed operation reports no final events
+        let dir = tempfile::tempdir().unwrap();
+        let stage = Stage::layers(RegisteredSealProof::StackedDrg2KiBV1_1, dir.path());
+        let (result, events) = record(stage, || {
+            write(dir.path(), "sc-02-data-layer-1.dat");
+            Err(anyhow!("failed"))
+        });
+        assert!(result.is_err());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn tree_and_partition_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let stage = Stage::trees(RegisteredSealProof::StackedDrg2KiBV1_1, dir.path());
+
+        let (result, events) = record(stage, || {
+            write(dir.path(), "sc-02-data-tree-c.dat");
+            write(dir.path(), "sc-02-data-tree-r-last.dat");
+            Ok(())
+        });
+        assert!(result.is_ok());
+        let percentages: Vec<_> = events.iter().map(|event| event.done).collect();
+        assert_eq!(percentages, vec![50, 100]);
+        assert!(events
+            .iter()
+            .all(|event| event.kind == SealProgressKind::TreeBuildPercentage));
+
+        let partitions = |done| SealProgress {
+            kind: SealProgressKind::PartitionProofDone,
+            done,
+            total: 10,
+        };
+        let stage = Stage::partitions(RegisteredSealProof::StackedDrg32GiBV1_1);
+        let (_, events) = record(stage, || Ok(()));
+        assert_eq!(events, vec![partitions(0), partitions(10)]);
+
+        let stage = Stage::partitions(RegisteredSealProof::StackedDrg32GiBV1_1);
+        let (_, events) = record(stage, || Err(anyhow!("failed")));
+        assert_eq!(events, vec![partitions(0)]);
+    }
+
+    #[test]
+    fn sector_shapes() {
+        let dir = Path::new("/");
+        assert_eq!(
+            Stage::layers(RegisteredSealProof::StackedDrg32GiBV1_1, dir).total(),
+            11
+        );
+        assert_eq!(
+            Stage::layers(RegisteredSealProof::StackedDrg512MiBV1_1, dir).total(),
+            2
+        );
+        assert_eq!(
+            Stage::trees(RegisteredSealProof::StackedDrg32GiBV1_1, dir).total(),
+            16
+        );
+        assert_eq!(
+            Stage::trees(RegisteredSealProof::StackedDrg64GiBV1_1, dir).total(),
+            32
+        );
+        assert_eq!(
+            Stage::trees(RegisteredSealProof::StackedDrg512MiBV1_1, dir).total(),
+            2
+        );
+    }
+}
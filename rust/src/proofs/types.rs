@@ -1,9 +1,68 @@
+use std::fmt;
 use std::io::SeekFrom;
+use std::sync::atomic::AtomicBool;
 
 use filecoin_proofs_api as api;
 use safer_ffi::prelude::*;
 
-use crate::util::types::Result;
+use crate::util::types::{FCPResponseStatus, Result};
+
+/// Error codes reported in the `error_code` field of the proofs responses, in the `1000..=1999`
+/// range of proofs codes. These are used by both rust and go, so don't remove or renumber them.
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum ProofsError {
+    /// A file doesn't exist, like the replica or a file in the cache directory.
+    MissingFile = 1001,
+    /// A file in the cache directory is truncated, or a tree doesn't match its commitment.
+    CorruptCache = 1002,
+    /// The proof type isn't supported by the operation, or different proof types are mixed.
+    InvalidProofType = 1003,
+    /// An input has the wrong length or is out of bounds.
+    InvalidInputLength = 1004,
+    /// Some sectors couldn't be proven, responses with a `faulty_sectors` field list them.
+    FaultySectors = 1005,
+    /// The Groth parameters or verifying keys are missing or don't match the manifest.
+    MissingParameters = 1006,
+}
+
+impl ProofsError {
+    /// The status reported along with the error code.
+    pub fn status(self) -> FCPResponseStatus {
+        match self {
+            ProofsError::InvalidProofType | ProofsError::InvalidInputLength => {
+                FCPResponseStatus::CallerError
+            }
+            ProofsError::MissingFile
+            | ProofsError::CorruptCache
+            | ProofsError::FaultySectors
+            | ProofsError::MissingParameters => FCPResponseStatus::ReceiverError,
+        }
+    }
+}
+
+impl fmt::Display for ProofsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            ProofsError::MissingFile => "missing file",
+            ProofsError::CorruptCache => "corrupt cache",
+            ProofsError::InvalidProofType => "invalid proof type",
+            ProofsError::InvalidInputLength => "invalid input length",
+            ProofsError::FaultySectors => "faulty sectors",
+            ProofsError::MissingParameters => "missing parameters",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for ProofsError {}
+
+// Dummy to make safer-ffi export the error enum
+#[ffi_export]
+fn dummy_proofs_error(_error: ProofsError) {
+    panic!("Don't call me");
+}
 
 /// FileDescriptorRef does not drop its file descriptor when it is dropped. Its
 /// owner must manage the lifecycle of the file descriptor.
@@ -438,6 +497,11 @@ pub struct GenerateFallbackSectorChallenges {
 
 pub type GenerateSingleVanillaProofResponse = Result<VanillaProof>;
 
+pub type VerifySingleVanillaProofResponse = Result<bool>;
+
+/// Whether each of the verified vanilla proofs is valid, in the order they were passed.
+pub type VerifySingleVanillaProofsResponse = Result<c_slice::Box<bool>>;
+
 pub type GenerateWinningPoStResponse = Result<c_slice::Box<PoStProof>>;
 
 pub type GenerateWindowPoStResponse = Result<GenerateWindowPoSt>;
@@ -519,6 +583,111 @@ pub type SealCommitPhase1Response = Result<c_slice::Box<u8>>;
 
 pub type SealCommitPhase2Response = Result<c_slice::Box<u8>>;
 
+pub type CheckSectorCacheResponse = Result<SectorCacheCheck>;
+
+/// Outcome of a single check of a [`SectorCacheCheck`].
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SectorCacheCheckStatus {
+    /// The check passed.
+    Ok = 0,
+    /// A file the check needs doesn't exist.
+    Missing = 1,
+    /// A file has the wrong size or content.
+    Invalid = 2,
+    /// The check wasn't run, as it depends on a check that didn't pass.
+    #[default]
+    Skipped = 3,
+}
+
+/// Report on the cache directory of a sealed sector, with one status per check.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SectorCacheCheck {
+    /// The replica exists and has the size of a sector.
+    pub replica: SectorCacheCheckStatus,
+    /// `p_aux` exists and holds comm_c and comm_r_last.
+    pub p_aux: SectorCacheCheckStatus,
+    /// `t_aux` exists and holds the configuration of the trees.
+    pub t_aux: SectorCacheCheckStatus,
+    /// All TreeRLast files exist and have the size the configuration implies.
+    pub tree_r_last_files: SectorCacheCheckStatus,
+    /// The root of TreeRLast matches comm_r_last of `p_aux`.
+    pub tree_r_last_root: SectorCacheCheckStatus,
+    /// comm_r is the hash of comm_c and comm_r_last of `p_aux`.
+    pub comm_r: SectorCacheCheckStatus,
+}
+
+/// Cancels the operations it is passed to when triggered, from any thread.
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(Debug, Default)]
+pub struct CancellationToken {
+    pub(crate) cancelled: AtomicBool,
+}
+
+/// Computes a piece commitment from data written to it in any number of parts.
+#[derive_ReprC]
+#[repr(opaque)]
+#[derive(Debug)]
+pub struct PieceCommitmentHasher {
+    /// Size of the piece the data is padded to at least, padded bytes, if a size was hinted.
+    pub(crate) min_padded_size: Option<u64>,
+    /// Number of bytes written, unpadded bytes.
+    pub(crate) written: u64,
+    /// Unpadded data that wasn't hashed yet.
+    pub(crate) buffer: Vec<u8>,
+    /// Roots of the subtrees hashed so far, the first one is of chunk size.
+    pub(crate) roots: Vec<Option<[u8; 32]>>,
+    /// Number of unpadded bytes hashed at once, a chunk for every thread of the pool the hasher
+    /// was created in.
+    pub(crate) batch_size: usize,
+}
+
+/// `value` holds the created hasher.
+pub type PieceCommitmentHasherResponse = Result<Option<repr_c::Box<PieceCommitmentHasher>>>;
+
+pub type PieceCommitmentResponse = Result<PieceCommitment>;
+
+pub type Fr32PadRangeResponse = Result<()>;
+pub type Fr32UnpadRangeResponse = Result<()>;
+pub type Fr32PadBytesResponse = Result<c_slice::Box<u8>>;
+pub type Fr32UnpadBytesResponse = Result<c_slice::Box<u8>>;
+
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct PieceCommitment {
+    pub comm_p: [u8; 32],
+    /// Size of the piece, the data padded to a whole merkle tree, in padded bytes.
+    pub padded_size: u64,
+}
+
+#[derive_ReprC]
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealProgressKind {
+    /// Label layer `done` of `total` is done.
+    LayerDone = 0,
+    /// `done` percent of the trees are built, `total` is always 100.
+    TreeBuildPercentage = 1,
+    /// Partition proof `done` of `total` is done. All partitions are proven in a single batch, so
+    /// `done` is only ever `0` when proving starts and `total` when it is done.
+    PartitionProofDone = 2,
+}
+
+/// A progress event of a sealing operation.
+#[derive_ReprC]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SealProgress {
+    pub kind: SealProgressKind,
+    pub done: u64,
+    pub total: u64,
+}
+
 #[derive_ReprC]
 #[repr(C)]
 #[derive(Clone, Default)]
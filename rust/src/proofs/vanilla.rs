@@ -0,0 +1,107 @@
+//! Verification of single vanilla PoSt proofs, as produced by `generate_single_vanilla_proof`.
+//!
+//! These are the checks the PoSt circuit makes for a sector, done outside of it, so a bad proof
+//! of a remote worker is found before the partition containing it is proven.
+
+use anyhow::Context;
+use filecoin_hashers::{HashFunction, Hasher};
+use filecoin_proofs::with_shape;
+use filecoin_proofs_api::{
+    Commitment, FallbackPoStSectorProof, MerkleTreeTrait, RegisteredPoStProof, SectorId, NODE_SIZE,
+};
+use storage_proofs_core::merkle::MerkleProofTrait;
+
+use super::types::ProofsError;
+
+/// Verifies that `vanilla_proof` proves `challenges` for the sector, returning whether it does.
+///
+/// Proofs that can't be deserialized are invalid. It is an error if the number of challenges
+/// doesn't match the proof type.
+pub(crate) fn verify_single_vanilla_proof(
+    registered_proof: RegisteredPoStProof,
+    sector_id: SectorId,
+    comm_r: &Commitment,
+    challenges: &[u64],
+    vanilla_proof: &[u8],
+) -> anyhow::Result<bool> {
+    let challenge_count = registered_proof.as_v1_config().challenge_count;
+    if challenges.len() != challenge_count {
+        return Err(ProofsError::InvalidInputLength).with_context(|| {
+            format!(
+                "expected {} challenges, got {}",
+                challenge_count,
+                challenges.len()
+            )
+        });
+    }
+
+    let sector_size = u64::from(registered_proof.sector_size());
+    Ok(with_shape!(
+        sector_size,
+        verify_inner,
+        sector_size,
+        sector_id,
+        comm_r,
+        challenges,
+        vanilla_proof,
+    ))
+}
+
+fn verify_inner<Tree: 'static + MerkleTreeTrait>(
+    sector_size: u64,
+    sector_id: SectorId,
+    comm_r: &Commitment,
+    challenges: &[u64],
+    vanilla_proof: &[u8],
+) -> bool {
+    let Ok(proof) = bincode::deserialize::<FallbackPoStSectorProof<Tree>>(vanilla_proof) else {
+        return false;
+    };
+    if proof.sector_id != sector_id || AsRef::<[u8]>::as_ref(&proof.comm_r) != comm_r {
+        return false;
+    }
+    let [sector] = &proof.vanilla_proof.sectors[..] else {
+        return false;
+    };
+    if sector.inclusion_proofs.len() != challenges.len() {
+        return false;
+    }
+
+    // comm_r = H(comm_c || comm_r_last)
+    let comm_r_last = sector.comm_r_last;
+    let hash = <Tree::Hasher as Hasher>::Function::hash2(&sector.comm_c, &comm_r_last);
+    if AsRef::<[u8]>::as_ref(&hash) != comm_r {
+        return false;
+    }
+
+    let leaves = sector_size as usize / NODE_SIZE;
+    sector
+        .inclusion_proofs
+        .iter()
+        .zip(challenges)
+        .all(|(inclusion_proof, &challenge)| {
+            inclusion_proof.root() == comm_r_last
+                && inclusion_proof.path().len() == inclusion_proof.expected_len(leaves)
+                && inclusion_proof.validate(challenge as usize)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proofs::helpers::classify_error;
+
+    #[test]
+    fn invalid_inputs() {
+        let proof = RegisteredPoStProof::StackedDrgWindow2KiBV1_2;
+        let challenges: Vec<u64> = (0..10).collect();
+
+        // garbage is an invalid proof rather than an error
+        let valid = verify_single_vanilla_proof(proof, 1.into(), &[0; 32], &challenges, b"garbage");
+        assert!(!valid.unwrap());
+
+        let err = verify_single_vanilla_proof(proof, 1.into(), &[0; 32], &challenges[1..], &[])
+            .unwrap_err();
+        assert_eq!(classify_error(&err), Some(ProofsError::InvalidInputLength));
+    }
+}
@@ -0,0 +1,124 @@
+use safer_ffi::prelude::*;
+
+use super::types::DomainSeparationTag;
+
+pub const RANDOMNESS_BYTES: usize = 32;
+
+pub type Randomness = [u8; RANDOMNESS_BYTES];
+
+#[ffi_export]
+fn destroy_box_randomness(ptr: repr_c::Box<Randomness>) {
+    drop(ptr);
+}
+
+/// Draw domain separated randomness from a randomness base
+///
+/// The result is `blake2b-256(personalization || blake2b-256(base) || round || entropy)`, with
+/// `personalization` and `round` encoded as 8 byte big endian integers, as defined by the
+/// protocol. The base is the 32 bytes returned by the chain or beacon randomness externs.
+///
+/// # Arguments
+///
+/// * `base`            - randomness base
+/// * `personalization` - domain separation tag
+/// * `round`           - epoch the randomness is drawn for
+/// * `entropy`         - additional entropy, usually the CBOR encoded miner address
+#[ffi_export]
+pub fn draw_randomness(
+    base: c_slice::Ref<'_, u8>,
+    personalization: i64,
+    round: i64,
+    entropy: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<Randomness> {
+    Box::new(draw_randomness_from_base(
+        &base,
+        personalization,
+        round,
+        &entropy,
+    ))
+    .into()
+}
+
+/// Same as [`draw_randomness`], for use from rust.
+pub fn draw_randomness_from_base(
+    base: &[u8],
+    personalization: i64,
+    round: i64,
+    entropy: &[u8],
+) -> Randomness {
+    let base_digest = blake2b_256().hash(base);
+
+    let hash = blake2b_256()
+        .to_state()
+        .update(&personalization.to_be_bytes())
+        .update(base_digest.as_bytes())
+        .update(&round.to_be_bytes())
+        .update(entropy)
+        .finalize();
+
+    hash.as_bytes().try_into().expect("known size")
+}
+
+/// Same as [`draw_randomness_from_base`], with one of the protocol's domain separation tags.
+pub fn draw_tagged_randomness(
+    base: &[u8],
+    tag: DomainSeparationTag,
+    round: i64,
+    entropy: &[u8],
+) -> Randomness {
+    draw_randomness_from_base(base, tag as i64, round, entropy)
+}
+
+fn blake2b_256() -> blake2b_simd::Params {
+    let mut params = blake2b_simd::Params::new();
+    params.hash_length(RANDOMNESS_BYTES);
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_vectors() {
+        let base: Vec<u8> = (0..32).collect();
+        let randomness = draw_randomness(base[..].into(), 2, 1000, [][..].into());
+        assert_eq!(
+            &randomness[..],
+            &unhex("9b6b4b5edd6e42ef229015e589d0f21b339b8b9d7bb15f76375903f742cee2e8")[..]
+        );
+        assert_eq!(
+            *randomness,
+            draw_tagged_randomness(
+                &base,
+                DomainSeparationTag::ElectionProofProduction,
+                1000,
+                &[]
+            )
+        );
+
+        let randomness = draw_tagged_randomness(
+            &[0xff; 32],
+            DomainSeparationTag::SealRandomness,
+            -1,
+            &[0x00, 0xa0, 0xe4],
+        );
+        assert_eq!(
+            &randomness[..],
+            &unhex("6c524365ba78abaeb12d7a116e40e3af72940aed6fa5c569475ad7b3536f8ee8")[..]
+        );
+
+        let randomness = draw_randomness_from_base(&[], 10, 1 << 62, b"entropy");
+        assert_eq!(
+            &randomness[..],
+            &unhex("a5737dff643f755d5008687fc5925990da1b076d1a08f905e1d2105b459b46de")[..]
+        );
+    }
+}
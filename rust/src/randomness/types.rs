@@ -0,0 +1,16 @@
+/// Domain separation tags of drawn randomness, the `personalization` of `draw_randomness`.
+/// These are defined by the protocol, so don't remove or renumber them.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[repr(i64)]
+pub enum DomainSeparationTag {
+    TicketProduction = 1,
+    ElectionProofProduction = 2,
+    WinningPoStChallengeSeed = 3,
+    WindowedPoStChallengeSeed = 4,
+    SealRandomness = 5,
+    InteractiveSealChallengeSeed = 6,
+    WindowedPoStDeadlineAssignment = 7,
+    MarketDealCronSeed = 8,
+    PoStChainCommit = 9,
+    EvmPrevRandao = 10,
+}
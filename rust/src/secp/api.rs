@@ -0,0 +1,245 @@
+use anyhow::{anyhow, ensure};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use rand::rngs::OsRng;
+use safer_ffi::prelude::*;
+
+use super::types::*;
+use crate::util::types::catch_panic_response;
+
+pub const SIGNATURE_BYTES: usize = 65;
+pub const PRIVATE_KEY_BYTES: usize = 32;
+pub const PUBLIC_KEY_BYTES: usize = 65;
+pub const DIGEST_BYTES: usize = 32;
+
+pub type SecpPrivateKey = [u8; PRIVATE_KEY_BYTES];
+
+#[ffi_export]
+fn destroy_box_secp_private_key(ptr: repr_c::Box<SecpPrivateKey>) {
+    drop(ptr);
+}
+
+/// Generate a new private key
+#[ffi_export]
+pub fn secp_private_key_generate() -> repr_c::Box<SecpPrivateKey> {
+    let private_key: SecpPrivateKey = SigningKey::random(&mut OsRng).to_bytes().into();
+
+    Box::new(private_key).into()
+}
+
+/// Generate the uncompressed public key (PUBLIC_KEY_BYTES long) for a private key
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+#[ffi_export]
+pub fn secp_private_key_public_key(
+    raw_private_key: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<SecpPublicKeyResponse> {
+    catch_panic_response("secp_private_key_public_key", || {
+        let private_key = decode_private_key(&raw_private_key)?;
+
+        Ok(public_key_bytes(private_key.verifying_key()))
+    })
+}
+
+/// Sign the blake2b-256 digest of a message with a private key and return the signature
+///
+/// The signature is SIGNATURE_BYTES long, the compact `r || s` encoding followed by the
+/// recovery id, the format used for Filecoin f1 addresses.
+///
+/// # Arguments
+///
+/// * `raw_private_key` - private key byte array
+/// * `message` - message byte array
+#[ffi_export]
+pub fn secp_private_key_sign(
+    raw_private_key: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<SecpSignatureResponse> {
+    catch_panic_response("secp_private_key_sign", || {
+        let private_key = decode_private_key(&raw_private_key)?;
+        let (signature, recovery_id) =
+            private_key.sign_prehash_recoverable(&blake2b_256(&message))?;
+
+        let mut raw_signature = signature.to_vec();
+        raw_signature.push(recovery_id.to_byte());
+
+        Ok(raw_signature.into_boxed_slice().into())
+    })
+}
+
+/// Verify that a signature was produced over the blake2b-256 digest of a message by the owner
+/// of the given public key
+///
+/// # Arguments
+///
+/// * `signature`  - signature byte array (SIGNATURE_BYTES long)
+/// * `message`    - message byte array
+/// * `public_key` - public key byte array, compressed (33 bytes) or uncompressed (65 bytes)
+#[ffi_export]
+pub fn secp_verify(
+    signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+    public_key: c_slice::Ref<'_, u8>,
+) -> bool {
+    let Ok(public_key) = VerifyingKey::from_sec1_bytes(&public_key) else {
+        return false;
+    };
+
+    matches!(recover(&signature, &message), Some(recovered) if recovered == public_key)
+}
+
+/// Recover the uncompressed public key (PUBLIC_KEY_BYTES long) that produced a signature over the
+/// blake2b-256 digest of a message
+///
+/// # Arguments
+///
+/// * `signature` - signature byte array (SIGNATURE_BYTES long)
+/// * `message`   - message byte array
+#[ffi_export]
+pub fn secp_recover_public_key(
+    signature: c_slice::Ref<'_, u8>,
+    message: c_slice::Ref<'_, u8>,
+) -> repr_c::Box<SecpPublicKeyResponse> {
+    catch_panic_response("secp_recover_public_key", || {
+        let public_key = recover(&signature, &message)
+            .ok_or_else(|| anyhow!("failed to recover public key from signature"))?;
+
+        Ok(public_key_bytes(&public_key))
+    })
+}
+
+#[ffi_export]
+fn destroy_secp_signature_response(ptr: repr_c::Box<SecpSignatureResponse>) {
+    drop(ptr);
+}
+
+#[ffi_export]
+fn destroy_secp_public_key_response(ptr: repr_c::Box<SecpPublicKeyResponse>) {
+    drop(ptr);
+}
+
+fn decode_private_key(raw_private_key: &[u8]) -> anyhow::Result<SigningKey> {
+    ensure!(
+        raw_private_key.len() == PRIVATE_KEY_BYTES,
+        "private key must be {} bytes, got {}",
+        PRIVATE_KEY_BYTES,
+        raw_private_key.len()
+    );
+
+    Ok(SigningKey::from_slice(raw_private_key)?)
+}
+
+fn blake2b_256(message: &[u8]) -> [u8; DIGEST_BYTES] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(DIGEST_BYTES)
+        .hash(message);
+
+    hash.as_bytes().try_into().expect("known size")
+}
+
+fn public_key_bytes(public_key: &VerifyingKey) -> c_slice::Box<u8> {
+    public_key
+        .to_encoded_point(false)
+        .as_bytes()
+        .to_vec()
+        .into_boxed_slice()
+        .into()
+}
+
+/// Recovers the public key from a 65 byte signature over the blake2b-256 digest of `message`.
+pub(crate) fn recover(signature: &[u8], message: &[u8]) -> Option<VerifyingKey> {
+    recover_from_digest(signature, &blake2b_256(message))
+}
+
+/// Recovers the public key from a 65 byte signature over `digest`.
+///
+/// Signatures with a high `s` value are normalized, flipping the recovery id, the same way the
+/// FVM does it.
+pub(crate) fn recover_from_digest(signature: &[u8], digest: &[u8]) -> Option<VerifyingKey> {
+    if signature.len() != SIGNATURE_BYTES {
+        return None;
+    }
+
+    let mut recovery_byte = signature[64];
+    let mut sig = Signature::from_slice(&signature[..64]).ok()?;
+    if let Some(normalized) = sig.normalize_s() {
+        sig = normalized;
+        recovery_byte ^= 1;
+    }
+    let recovery_id = RecoveryId::from_byte(recovery_byte)?;
+
+    VerifyingKey::recover_from_prehash(digest, &sig, recovery_id).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::types::FCPResponseStatus;
+
+    #[test]
+    fn key_verification() {
+        let private_key = secp_private_key_generate();
+        let public_key = secp_private_key_public_key(private_key[..].into());
+        assert_eq!(public_key.status_code, FCPResponseStatus::NoError);
+        assert_eq!(public_key.value.len(), PUBLIC_KEY_BYTES);
+
+        let message = b"hello world";
+        let signature = secp_private_key_sign(private_key[..].into(), message[..].into());
+        assert_eq!(signature.status_code, FCPResponseStatus::NoError);
+        assert_eq!(signature.value.len(), SIGNATURE_BYTES);
+
+        assert!(secp_verify(
+            signature.value.as_ref(),
+            message[..].into(),
+            public_key.value.as_ref(),
+        ));
+
+        let recovered = secp_recover_public_key(signature.value.as_ref(), message[..].into());
+        assert_eq!(recovered.status_code, FCPResponseStatus::NoError);
+        assert_eq!(&recovered.value[..], &public_key.value[..]);
+
+        assert!(!secp_verify(
+            signature.value.as_ref(),
+            b"bye world"[..].into(),
+            public_key.value.as_ref(),
+        ));
+
+        let other_private_key = secp_private_key_generate();
+        let other_public_key = secp_private_key_public_key(other_private_key[..].into());
+        assert!(!secp_verify(
+            signature.value.as_ref(),
+            message[..].into(),
+            other_public_key.value.as_ref(),
+        ));
+
+        // garbage
+        assert!(!secp_verify(
+            signature.value[..64].into(),
+            message[..].into(),
+            public_key.value.as_ref(),
+        ));
+        let recovered = secp_recover_public_key(signature.value[..64].into(), message[..].into());
+        assert_eq!(recovered.status_code, FCPResponseStatus::UnclassifiedError);
+        let signature = secp_private_key_sign([0u8; 32][..].into(), message[..].into());
+        assert_eq!(signature.status_code, FCPResponseStatus::UnclassifiedError);
+        let public_key = secp_private_key_public_key([1u8; 31][..].into());
+        assert_eq!(public_key.status_code, FCPResponseStatus::UnclassifiedError);
+    }
+
+    #[cfg(feature = "fvm")]
+    #[test]
+    fn matches_fvm_recovery() {
+        use fvm4_shared::crypto::signature::ops::recover_secp_public_key;
+
+        let private_key = secp_private_key_generate();
+        let message = b"a filecoin message";
+        let signature = secp_private_key_sign(private_key[..].into(), message[..].into());
+        let raw_signature: [u8; SIGNATURE_BYTES] = signature.value[..].try_into().unwrap();
+
+        let expected = recover_secp_public_key(&blake2b_256(message), &raw_signature).unwrap();
+        let recovered = secp_recover_public_key(signature.value.as_ref(), message[..].into());
+
+        assert_eq!(&recovered.value[..], &expected[..]);
+    }
+}
@@ -0,0 +1,9 @@
+use safer_ffi::prelude::*;
+
+use crate::util::types::Result;
+
+/// `value` holds a recoverable signature (`SIGNATURE_BYTES` long).
+pub type SecpSignatureResponse = Result<c_slice::Box<u8>>;
+
+/// `value` holds an uncompressed public key (`PUBLIC_KEY_BYTES` long).
+pub type SecpPublicKeyResponse = Result<c_slice::Box<u8>>;
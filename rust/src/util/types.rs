@@ -13,8 +13,23 @@ pub enum FCPResponseStatus {
     UnclassifiedError = 1,
     CallerError = 2,
     ReceiverError = 3,
+    /// The operation was cancelled through its cancellation token.
+    Cancelled = 4,
 }
 
+/// Error of an operation that was cancelled, reported with the [`FCPResponseStatus::Cancelled`]
+/// status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
 #[cfg(target_os = "linux")]
 pub fn as_path_buf(bytes: &[u8]) -> std::result::Result<PathBuf, Utf8Error> {
     use std::ffi::OsStr;
@@ -46,6 +61,11 @@ pub fn as_bytes(path: &std::path::Path) -> &[u8] {
 #[derive(Clone)]
 pub struct Result<T: Sized> {
     pub status_code: FCPResponseStatus,
+    /// Code further classifying an error, `0` if there is none. Each module reports codes from its
+    /// own range, so the code alone tells which module set it: `1..=999` are
+    /// [`crate::bls::types::BLSError`]s and `1000..=1999` are
+    /// [`crate::proofs::types::ProofsError`]s.
+    pub error_code: i32,
     pub error_msg: c_slice::Box<u8>,
     pub value: T,
 }
@@ -62,6 +82,7 @@ impl<T: Sized + Default> Default for Result<T> {
     fn default() -> Self {
         Result {
             status_code: FCPResponseStatus::NoError,
+            error_code: 0,
             error_msg: Default::default(),
             value: Default::default(),
         }
@@ -88,6 +109,7 @@ where
     fn from(value: T) -> Self {
         Self {
             status_code: FCPResponseStatus::NoError,
+            error_code: 0,
             error_msg: Default::default(),
             value,
         }
@@ -98,6 +120,7 @@ impl<T: Sized> Result<T> {
     pub fn ok(value: T) -> Self {
         Result {
             status_code: FCPResponseStatus::NoError,
+            error_code: 0,
             error_msg: Default::default(),
             value,
         }
@@ -110,6 +133,7 @@ impl<T: Sized> Result<T> {
     pub fn err_with_default(err: impl Into<c_slice::Box<u8>>, value: T) -> Self {
         Result {
             status_code: FCPResponseStatus::UnclassifiedError,
+            error_code: 0,
             error_msg: err.into(),
             value,
         }
@@ -119,6 +143,7 @@ impl<T: Sized> Result<T> {
     pub unsafe fn err_no_default(err: impl Into<c_slice::Box<u8>>) -> Self {
         Result {
             status_code: FCPResponseStatus::UnclassifiedError,
+            error_code: 0,
             error_msg: err.into(),
             value: MaybeUninit::zeroed().assume_init(),
         }
@@ -129,6 +154,20 @@ impl<T: Sized + Default> Result<T> {
     pub fn err(err: impl Into<c_slice::Box<u8>>) -> Self {
         Result {
             status_code: FCPResponseStatus::UnclassifiedError,
+            error_code: 0,
+            error_msg: err.into(),
+            value: Default::default(),
+        }
+    }
+
+    pub fn err_with_code(
+        status_code: FCPResponseStatus,
+        error_code: i32,
+        err: impl Into<c_slice::Box<u8>>,
+    ) -> Self {
+        Result {
+            status_code,
+            error_code,
             error_msg: err.into(),
             value: Default::default(),
         }
@@ -155,9 +194,7 @@ where
     T: Sized + Default,
     F: FnOnce() -> anyhow::Result<T> + std::panic::UnwindSafe,
 {
-    catch_panic_response_raw(name, || {
-        Result::from(callback().map_err(|err| format!("{err:?}")))
-    })
+    catch_panic_response_raw(name, || from_anyhow(callback()))
 }
 
 pub fn catch_panic_response_no_log<F, T>(callback: F) -> repr_c::Box<Result<T>>
@@ -165,7 +202,20 @@ where
     T: Sized + Default,
     F: FnOnce() -> anyhow::Result<T> + std::panic::UnwindSafe,
 {
-    catch_panic_response_raw_no_log(|| Result::from(callback().map_err(|err| format!("{err:?}"))))
+    catch_panic_response_raw_no_log(|| from_anyhow(callback()))
+}
+
+/// Converts the outcome of an operation into a response, all errors but [`Cancelled`] are
+/// unclassified.
+pub(crate) fn from_anyhow<T: Sized + Default>(result: anyhow::Result<T>) -> Result<T> {
+    match result {
+        Err(err) if err.is::<Cancelled>() => Result::err_with_code(
+            FCPResponseStatus::Cancelled,
+            0,
+            err.to_string().into_bytes().into_boxed_slice(),
+        ),
+        result => Result::from(result.map_err(|err| format!("{err:?}"))),
+    }
 }
 
 pub fn catch_panic_response_raw_no_log<F, T>(callback: F) -> repr_c::Box<Result<T>>